@@ -34,7 +34,8 @@ pub(crate) fn astar(
     map_dimension: Vector2<f64>,
     offset: Vector2<f64>,
     step_size: f64,
-    mut is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
+    mut step_cost: impl FnMut(Vector2<f64>, Vector2<f64>) -> Option<f64>,
+    mut on_explore: impl FnMut(Vector2<f64>),
 ) -> Vec<Vector2<f64>> {
     let startf = start;
     let goalf = goal;
@@ -84,6 +85,8 @@ pub(crate) fn astar(
 
     let mut parents: FxHashMap<Vector2<u32>, Parent> = FxHashMap::default();
     parents.insert(start, Parent::Start);
+    let mut costs: FxHashMap<Vector2<u32>, usize> = FxHashMap::default();
+    costs.insert(start, 0);
     let mut to_see: BinaryHeap<HeapElement> = BinaryHeap::default();
     let mut best_cost_so_far = Cost {
         heuristic: usize::MAX,
@@ -97,9 +100,13 @@ pub(crate) fn astar(
     let mut best_so_far = start;
 
     while let Some(HeapElement { node, cost }) = to_see.pop() {
+        if costs.get(&node).is_some_and(|&best| cost.cost > best) {
+            // A cheaper way to this node was found after this element was pushed.
+            continue;
+        }
+        on_explore(step_size * node.cast() + offset);
         let successors = {
             if node == goal {
-                best_cost_so_far = cost;
                 best_so_far = node;
                 break;
             } else if cost.heuristic < best_cost_so_far.heuristic {
@@ -110,10 +117,11 @@ pub(crate) fn astar(
             let node_parent = parents.get(&node).unwrap();
             let mut successors = heapless::Vec::<_, 8>::new();
             let mut try_add = |next: Vector2<u32>, successor_parent: Parent, cost: usize| {
-                if is_safe(
+                if let Some(extra) = step_cost(
                     step_size * node.cast() + offset,
                     step_size * next.cast() + offset,
                 ) {
+                    let cost = (cost as f64 * (1.0 + extra)).round() as usize;
                     successors.push((next, successor_parent, cost)).unwrap();
                 }
             };
@@ -163,6 +171,11 @@ pub(crate) fn astar(
 
         for (successor, parent, added_cost) in successors {
             let new_cost = cost.cost + added_cost;
+            if costs.get(&successor).is_some_and(|&best| new_cost >= best) {
+                continue;
+            }
+            costs.insert(successor, new_cost);
+            parents.insert(successor, parent);
             let successor_cost = Cost {
                 heuristic: heuristic(successor),
                 cost: new_cost,
@@ -173,47 +186,29 @@ pub(crate) fn astar(
                 node: successor,
                 cost: successor_cost,
             });
-            let _ = parents.try_insert(successor, parent);
         }
     }
 
-    let mut path = vec![goalf; best_cost_so_far.length];
-
-    for i in (1..best_cost_so_far.length - 1).rev() {
-        macro_rules! traverse {
-            () => {
-                best_so_far = match parents.get(&best_so_far).unwrap() {
-                    Parent::NegX => best_so_far - Vector2::new(1, 0),
-                    Parent::NegY => best_so_far - Vector2::new(0, 1),
-                    Parent::PosX => best_so_far + Vector2::new(1, 0),
-                    Parent::PosY => best_so_far + Vector2::new(0, 1),
-                    Parent::NegXNegY => best_so_far - Vector2::new(1, 1),
-                    Parent::NegXPosY => best_so_far - Vector2::new(1, 0) + Vector2::new(0, 1),
-                    Parent::PosXNegY => best_so_far + Vector2::new(1, 0) - Vector2::new(0, 1),
-                    Parent::PosXPosY => best_so_far + Vector2::new(1, 1),
-                    Parent::Start => unreachable!(),
-                };
-            };
-        }
-        traverse!();
-        path[i] = step_size * best_so_far.cast() + offset;
-
-        #[cfg(debug_assertions)]
-        {
-            if i == 1 {
-                let pre = best_so_far;
-                traverse!();
-                assert_eq!(
-                    best_so_far,
-                    start,
-                    "pre: {pre:?} {:?}",
-                    parents.get(&pre).unwrap()
-                );
-            }
-        }
+    let mut path = vec![goalf];
+    let mut node = best_so_far;
+    loop {
+        node = match parents.get(&node).unwrap() {
+            Parent::NegX => node - Vector2::new(1, 0),
+            Parent::NegY => node - Vector2::new(0, 1),
+            Parent::PosX => node + Vector2::new(1, 0),
+            Parent::PosY => node + Vector2::new(0, 1),
+            Parent::NegXNegY => node - Vector2::new(1, 1),
+            Parent::NegXPosY => node - Vector2::new(1, 0) + Vector2::new(0, 1),
+            Parent::PosXNegY => node + Vector2::new(1, 0) - Vector2::new(0, 1),
+            Parent::PosXPosY => node + Vector2::new(1, 1),
+            Parent::Start => break,
+        };
+        path.push(step_size * node.cast() + offset);
     }
+    debug_assert_eq!(node, start);
 
-    path[0] = startf;
+    *path.last_mut().unwrap() = startf;
+    path.reverse();
     path
 }
 
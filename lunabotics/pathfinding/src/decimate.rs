@@ -6,11 +6,27 @@ thread_local! {
     static DECIMATE_BUFFER: RefCell<Vec<Vector2<f64>>> = RefCell::new(Vec::new());
 }
 
+/// Returns the distance from `point` to the line segment between `from` and `to`.
+fn distance_to_segment(point: Vector2<f64>, from: Vector2<f64>, to: Vector2<f64>) -> f64 {
+    let segment = to - from;
+    let length_squared = segment.magnitude_squared();
+    if length_squared == 0.0 {
+        return (point - from).magnitude();
+    }
+    let t = ((point - from).dot(&segment) / length_squared).clamp(0.0, 1.0);
+    (point - (from + segment * t)).magnitude()
+}
+
 /// Simplifies the given path by taking safe shortcuts.
 ///
+/// A shortcut is only taken if every waypoint it skips is within `tolerance`
+/// of the shortcut. A tolerance of `0.0` only removes collinear waypoints,
+/// while `f64::INFINITY` takes any shortcut that is safe.
+///
 /// The capacity of the given vector may change.
 pub(crate) fn decimate(
     path: &mut Vec<Vector2<f64>>,
+    tolerance: f64,
     mut is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
 ) {
     if path.len() < 3 {
@@ -18,6 +34,7 @@ pub(crate) fn decimate(
     }
     DECIMATE_BUFFER.with_borrow_mut(|buffer| {
         buffer.clear();
+        let mut from_index = 0;
         let mut from = path[0];
         buffer.push(from);
 
@@ -28,10 +45,14 @@ pub(crate) fn decimate(
 
             loop {
                 to = path[to_index];
-                if path[to_index - 1] == from {
+                if to_index - 1 == from_index {
                     break;
                 }
-                if is_safe(from, to) {
+                if path[from_index + 1..to_index]
+                    .iter()
+                    .all(|&skipped| distance_to_segment(skipped, from, to) <= tolerance)
+                    && is_safe(from, to)
+                {
                     break;
                 }
                 to_index -= 1;
@@ -40,6 +61,7 @@ pub(crate) fn decimate(
 
             buffer.push(to);
             from = to;
+            from_index = to_index;
             if !shortened {
                 break;
             }
@@ -47,3 +69,25 @@ pub(crate) fn decimate(
         std::mem::swap(path, buffer);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zig_zag() -> Vec<Vector2<f64>> {
+        (0..=10)
+            .map(|i| Vector2::new(i as f64, if i % 2 == 0 { 0.0 } else { 0.5 }))
+            .collect()
+    }
+
+    #[test]
+    fn test_decimate_tolerance() {
+        let mut strict = zig_zag();
+        decimate(&mut strict, 0.0, |_, _| true);
+        assert_eq!(strict, zig_zag());
+
+        let mut loose = zig_zag();
+        decimate(&mut loose, 1.0, |_, _| true);
+        assert_eq!(loose, [Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0)]);
+    }
+}
@@ -1,10 +1,9 @@
-#![feature(map_try_insert)]
-
 use nalgebra::Vector2;
 
 mod astar;
 mod decimate;
 pub mod obstacles;
+pub mod trajectory;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Pathfinder<F = ()> {
@@ -16,32 +15,134 @@ pub struct Pathfinder<F = ()> {
     pub offset: Vector2<f64>,
     /// The distance between points in the path.
     pub step_size: f64,
+    /// How far a waypoint removed during path simplification may be from the shortcut
+    /// that replaces it.
+    ///
+    /// By default, this is `f64::INFINITY`, so any safe shortcut is taken.
+    pub decimate_tolerance: f64,
     /// A closure that returns whether a point is safe to traverse.
     ///
     /// If this is `()`, a function must be provided when calling `pathfind`.
+    /// If this is a `CostField`, paths minimize traversal cost instead of only avoiding
+    /// unsafe points.
     pub is_safe: F,
 }
 
+/// A closure that returns the cost of traversing a point, for use with `Pathfinder::with_cost`.
+///
+/// `None` means the point is impassable. `Some(c)` means that travelling through the point
+/// costs `c` more per unit of distance than travelling over free ground, so a cost of `1.0`
+/// makes a route through the point count as twice as long. Costs must not be negative.
+#[derive(Clone, Copy, Debug)]
+pub struct CostField<F>(pub F);
+
+/// Returns `true` if all the given values are finite and `step_size` is positive.
+///
+/// Non-finite inputs can cause A* to loop forever or panic, and usually come from
+/// a bad localization estimate.
+fn inputs_are_valid(points: &[Vector2<f64>], step_size: f64) -> bool {
+    step_size.is_finite()
+        && step_size > 0.0
+        && points
+            .iter()
+            .all(|point| point.x.is_finite() && point.y.is_finite())
+}
+
+/// Checks if the straight line from `a` to `b` is safe.
+///
+/// The line is split into segments of `step_size` (except for the last one, which may be
+/// shorter), and each segment is checked with `is_safe`, the same way A* checks its steps.
+fn line_of_sight(
+    a: Vector2<f64>,
+    b: Vector2<f64>,
+    step_size: f64,
+    mut is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
+) -> bool {
+    if !inputs_are_valid(&[a, b], step_size) {
+        return false;
+    }
+    let mut travel = b - a;
+    let distance = travel.magnitude();
+    if distance == 0.0 {
+        return true;
+    }
+    travel.unscale_mut(distance);
+    let count = (distance / step_size).floor() as usize;
+
+    let mut from = a;
+    for i in 1..=count {
+        let to = a + travel * (step_size * i as f64);
+        if !is_safe(from, to) {
+            return false;
+        }
+        from = to;
+    }
+    from == b || is_safe(from, b)
+}
+
 impl<F: FnMut(Vector2<f64>, Vector2<f64>) -> bool> Pathfinder<F> {
     pub fn new(map_dimension: Vector2<f64>, step_size: f64, is_safe: F) -> Self {
         Self {
             map_dimension,
             offset: Vector2::new(0.0, 0.0),
             step_size,
+            decimate_tolerance: f64::INFINITY,
             is_safe,
         }
     }
 
+    /// Checks if the straight line from `a` to `b` is safe, without pathfinding.
+    pub fn line_of_sight(&mut self, a: Vector2<f64>, b: Vector2<f64>) -> bool {
+        line_of_sight(a, b, self.step_size, &mut self.is_safe)
+    }
+
+    /// Finds a safe path from `start` to `goal`.
+    ///
+    /// An empty path is returned if any of the inputs or the dimensions of this `Pathfinder`
+    /// are not finite, or if `step_size` is not positive.
     pub fn pathfind(&mut self, start: Vector2<f64>, goal: Vector2<f64>) -> Vec<Vector2<f64>> {
+        self.pathfind_debug_inner(start, goal, |_| {})
+    }
+
+    /// The same as `pathfind`, except that every point that A* explored is also returned.
+    ///
+    /// This is meant for visualizing the search. The explored points can number in the
+    /// tens of thousands on large, open maps. No points are explored if `start` can see `goal`.
+    pub fn pathfind_debug(
+        &mut self,
+        start: Vector2<f64>,
+        goal: Vector2<f64>,
+    ) -> (Vec<Vector2<f64>>, Vec<Vector2<f64>>) {
+        let mut explored = Vec::new();
+        let path = self.pathfind_debug_inner(start, goal, |point| explored.push(point));
+        (path, explored)
+    }
+
+    fn pathfind_debug_inner(
+        &mut self,
+        start: Vector2<f64>,
+        goal: Vector2<f64>,
+        on_explore: impl FnMut(Vector2<f64>),
+    ) -> Vec<Vector2<f64>> {
+        if !inputs_are_valid(
+            &[start, goal, self.map_dimension, self.offset],
+            self.step_size,
+        ) {
+            return Vec::new();
+        }
+        if self.line_of_sight(start, goal) {
+            return vec![start, goal];
+        }
         let mut path = astar::astar(
             start,
             goal,
             self.map_dimension,
             self.offset,
             self.step_size,
-            &mut self.is_safe,
+            |from, to| (self.is_safe)(from, to).then_some(0.0),
+            on_explore,
         );
-        decimate::decimate(&mut path, &mut self.is_safe);
+        decimate::decimate(&mut path, self.decimate_tolerance, &mut self.is_safe);
         path
     }
 }
@@ -52,29 +153,235 @@ impl Pathfinder<()> {
             map_dimension,
             offset: Vector2::new(0.0, 0.0),
             step_size,
+            decimate_tolerance: f64::INFINITY,
             is_safe: (),
         }
     }
 
+    /// Checks if the straight line from `a` to `b` is safe, without pathfinding.
+    pub fn line_of_sight(
+        &mut self,
+        a: Vector2<f64>,
+        b: Vector2<f64>,
+        is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
+    ) -> bool {
+        line_of_sight(a, b, self.step_size, is_safe)
+    }
+
+    /// Finds a safe path from `start` to `goal`.
+    ///
+    /// An empty path is returned if any of the inputs or the dimensions of this `Pathfinder`
+    /// are not finite, or if `step_size` is not positive.
     pub fn pathfind(
+        &mut self,
+        start: Vector2<f64>,
+        goal: Vector2<f64>,
+        is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
+    ) -> Vec<Vector2<f64>> {
+        self.pathfind_debug_inner(start, goal, is_safe, |_| {})
+    }
+
+    /// The same as `pathfind`, except that every point that A* explored is also returned.
+    ///
+    /// This is meant for visualizing the search. The explored points can number in the
+    /// tens of thousands on large, open maps. No points are explored if `start` can see `goal`.
+    pub fn pathfind_debug(
+        &mut self,
+        start: Vector2<f64>,
+        goal: Vector2<f64>,
+        is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
+    ) -> (Vec<Vector2<f64>>, Vec<Vector2<f64>>) {
+        let mut explored = Vec::new();
+        let path = self.pathfind_debug_inner(start, goal, is_safe, |point| explored.push(point));
+        (path, explored)
+    }
+
+    fn pathfind_debug_inner(
         &mut self,
         start: Vector2<f64>,
         goal: Vector2<f64>,
         mut is_safe: impl FnMut(Vector2<f64>, Vector2<f64>) -> bool,
+        on_explore: impl FnMut(Vector2<f64>),
+    ) -> Vec<Vector2<f64>> {
+        if !inputs_are_valid(
+            &[start, goal, self.map_dimension, self.offset],
+            self.step_size,
+        ) {
+            return Vec::new();
+        }
+        if self.line_of_sight(start, goal, &mut is_safe) {
+            return vec![start, goal];
+        }
+        let mut path = astar::astar(
+            start,
+            goal,
+            self.map_dimension,
+            self.offset,
+            self.step_size,
+            |from, to| is_safe(from, to).then_some(0.0),
+            on_explore,
+        );
+        decimate::decimate(&mut path, self.decimate_tolerance, &mut is_safe);
+        path
+    }
+}
+
+impl<F: FnMut(Vector2<f64>) -> Option<f64>> Pathfinder<CostField<F>> {
+    /// Creates a `Pathfinder` that finds the cheapest path through the given cost field,
+    /// instead of the shortest safe path.
+    ///
+    /// See `CostField` for the meaning of the costs.
+    pub fn with_cost(map_dimension: Vector2<f64>, step_size: f64, cost_fn: F) -> Self {
+        Self {
+            map_dimension,
+            offset: Vector2::new(0.0, 0.0),
+            step_size,
+            decimate_tolerance: f64::INFINITY,
+            is_safe: CostField(cost_fn),
+        }
+    }
+
+    /// Checks if the straight line from `a` to `b` only crosses points with no extra cost.
+    ///
+    /// Shortcuts are only taken over such lines, as a shortcut through costly points may be
+    /// more expensive than the path it replaces.
+    pub fn line_of_sight(&mut self, a: Vector2<f64>, b: Vector2<f64>) -> bool {
+        line_of_sight(a, b, self.step_size, |_, to| {
+            (self.is_safe.0)(to).is_some_and(|cost| cost <= 0.0)
+        })
+    }
+
+    /// Finds the cheapest path from `start` to `goal`.
+    ///
+    /// An empty path is returned if any of the inputs or the dimensions of this `Pathfinder`
+    /// are not finite, or if `step_size` is not positive.
+    pub fn pathfind(&mut self, start: Vector2<f64>, goal: Vector2<f64>) -> Vec<Vector2<f64>> {
+        self.pathfind_debug_inner(start, goal, |_| {})
+    }
+
+    /// The same as `pathfind`, except that every point that A* explored is also returned.
+    ///
+    /// This is meant for visualizing the search. No points are explored if `start` can see `goal`.
+    pub fn pathfind_debug(
+        &mut self,
+        start: Vector2<f64>,
+        goal: Vector2<f64>,
+    ) -> (Vec<Vector2<f64>>, Vec<Vector2<f64>>) {
+        let mut explored = Vec::new();
+        let path = self.pathfind_debug_inner(start, goal, |point| explored.push(point));
+        (path, explored)
+    }
+
+    fn pathfind_debug_inner(
+        &mut self,
+        start: Vector2<f64>,
+        goal: Vector2<f64>,
+        on_explore: impl FnMut(Vector2<f64>),
     ) -> Vec<Vector2<f64>> {
+        if !inputs_are_valid(
+            &[start, goal, self.map_dimension, self.offset],
+            self.step_size,
+        ) {
+            return Vec::new();
+        }
+        if self.line_of_sight(start, goal) {
+            return vec![start, goal];
+        }
         let mut path = astar::astar(
             start,
             goal,
             self.map_dimension,
             self.offset,
             self.step_size,
-            &mut is_safe,
+            |_, to| (self.is_safe.0)(to),
+            on_explore,
         );
-        decimate::decimate(&mut path, &mut is_safe);
+        decimate::decimate(&mut path, self.decimate_tolerance, |from, to| {
+            self.line_of_sight(from, to)
+        });
         path
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_of_sight_clear() {
+        let mut pathfinder =
+            Pathfinder::<fn(_, _) -> bool>::new(Vector2::new(10.0, 10.0), 0.5, |_, _| true);
+        assert!(pathfinder.line_of_sight(Vector2::new(1.0, 1.0), Vector2::new(8.2, 3.7)));
+    }
+
+    #[test]
+    fn test_line_of_sight_blocked() {
+        let mut pathfinder = Pathfinder::<()>::new(Vector2::new(10.0, 10.0), 0.5);
+        let wall = |from: Vector2<f64>, to: Vector2<f64>| from.x < 5.0 && to.x < 5.0;
+        assert!(!pathfinder.line_of_sight(Vector2::new(1.0, 1.0), Vector2::new(8.0, 1.0), wall));
+        assert!(pathfinder.line_of_sight(Vector2::new(1.0, 1.0), Vector2::new(4.0, 8.0), wall));
+    }
+
+    #[test]
+    fn test_nan_start() {
+        let mut pathfinder = Pathfinder::<()>::new(Vector2::new(10.0, 10.0), 0.5);
+        let path = pathfinder.pathfind(
+            Vector2::new(f64::NAN, 1.0),
+            Vector2::new(8.0, 8.0),
+            |_, _| true,
+        );
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_pathfind_debug_explored() {
+        let mut pathfinder = Pathfinder::<()>::new(Vector2::new(10.0, 10.0), 0.5);
+        let wall =
+            |from: Vector2<f64>, to: Vector2<f64>| !(from.x < 5.0 && to.x >= 5.0 && to.y < 8.0);
+        let goal = Vector2::new(8.0, 1.0);
+        let (path, explored) = pathfinder.pathfind_debug(Vector2::new(1.0, 1.0), goal, wall);
+        assert_eq!(path.last(), Some(&goal));
+        assert!(explored.len() > path.len());
+        assert!(explored.contains(&Vector2::new(1.0, 1.0)));
+
+        let (_, explored) =
+            pathfinder.pathfind_debug(Vector2::new(1.0, 1.0), Vector2::new(1.0, 4.0), wall);
+        assert!(explored.is_empty());
+    }
+
+    #[test]
+    fn test_cheaper_detour() {
+        // A strip of rough terrain that costs far more to cross than to go around.
+        let in_strip = |point: Vector2<f64>| (4.0..=6.0).contains(&point.x) && point.y >= 3.0;
+        let start = Vector2::new(1.0, 5.0);
+        let goal = Vector2::new(9.0, 5.0);
+
+        let mut pathfinder = Pathfinder::<()>::new(Vector2::new(10.0, 10.0), 0.5);
+        let path = pathfinder.pathfind(start, goal, |_, _| true);
+        assert_eq!(path, [start, goal]);
+
+        let mut pathfinder = Pathfinder::with_cost(Vector2::new(10.0, 10.0), 0.5, |point| {
+            Some(if in_strip(point) { 5.0 } else { 0.0 })
+        });
+        let path = pathfinder.pathfind(start, goal);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for pair in path.windows(2) {
+            for i in 0..=10 {
+                let point = pair[0].lerp(&pair[1], i as f64 / 10.0);
+                assert!(point.y < 3.0 || !(4.5..=5.5).contains(&point.x), "{path:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_step_size() {
+        let mut pathfinder = Pathfinder::<()>::new(Vector2::new(10.0, 10.0), 0.0);
+        let path = pathfinder.pathfind(Vector2::new(1.0, 1.0), Vector2::new(8.0, 8.0), |_, _| true);
+        assert!(path.is_empty());
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::*;
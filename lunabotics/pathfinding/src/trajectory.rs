@@ -0,0 +1,146 @@
+use nalgebra::Vector2;
+
+/// A waypoint with the time at which it should be reached.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedWaypoint {
+    pub point: Vector2<f64>,
+    /// The time to reach `point`, in seconds since the start of the path.
+    pub time: f64,
+    /// The speed when passing through `point`.
+    pub speed: f64,
+}
+
+/// Assigns arrival times to the waypoints of a path.
+///
+/// The robot starts and stops at rest. Between waypoints, it accelerates at `max_accel` up
+/// to `max_vel`, cruises, then decelerates at `max_accel` (a trapezoidal velocity profile).
+/// The speed through each corner is limited by how sharply the path turns, from `max_vel`
+/// for a straight line down to zero for a full reversal, and the robot starts slowing down
+/// early enough to reach that speed.
+///
+/// This works with the waypoints of any pathfinder. `None` is returned if either limit is
+/// not positive and finite, or if any waypoint is not finite.
+pub fn parameterize(
+    path: &[Vector2<f64>],
+    max_vel: f64,
+    max_accel: f64,
+) -> Option<Vec<TimedWaypoint>> {
+    if !(max_vel.is_finite() && max_vel > 0.0 && max_accel.is_finite() && max_accel > 0.0) {
+        return None;
+    }
+    if !path
+        .iter()
+        .all(|point| point.x.is_finite() && point.y.is_finite())
+    {
+        return None;
+    }
+    let lengths: Vec<f64> = path
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).magnitude())
+        .collect();
+
+    // The fastest speed allowed through each waypoint, based on how sharply the path turns there.
+    let mut speeds: Vec<f64> = (0..path.len())
+        .map(|i| {
+            if i == 0 || i + 1 == path.len() {
+                return 0.0;
+            }
+            let incoming = path[i] - path[i - 1];
+            let outgoing = path[i + 1] - path[i];
+            let norms = incoming.magnitude() * outgoing.magnitude();
+            if norms <= 0.0 {
+                return max_vel;
+            }
+            let cos_turn = (incoming.dot(&outgoing) / norms).clamp(-1.0, 1.0);
+            max_vel * (1.0 + cos_turn) / 2.0
+        })
+        .collect();
+
+    // Limit each speed to what can be reached by accelerating from the previous waypoint,
+    // then to what can be slowed down from before the next one.
+    for i in 1..speeds.len() {
+        let reachable = (speeds[i - 1].powi(2) + 2.0 * max_accel * lengths[i - 1]).sqrt();
+        speeds[i] = speeds[i].min(reachable);
+    }
+    for i in (0..speeds.len().saturating_sub(1)).rev() {
+        let stoppable = (speeds[i + 1].powi(2) + 2.0 * max_accel * lengths[i]).sqrt();
+        speeds[i] = speeds[i].min(stoppable);
+    }
+
+    let mut time = 0.0;
+    let timed = path
+        .iter()
+        .zip(&speeds)
+        .enumerate()
+        .map(|(i, (&point, &speed))| {
+            if i > 0 {
+                time += segment_time(lengths[i - 1], speeds[i - 1], speed, max_vel, max_accel);
+            }
+            TimedWaypoint { point, time, speed }
+        })
+        .collect();
+    Some(timed)
+}
+
+/// Returns the time to travel `length` starting at speed `v0` and ending at speed `v1`,
+/// using a trapezoidal velocity profile.
+///
+/// `max_vel` and `max_accel` must be positive.
+fn segment_time(length: f64, v0: f64, v1: f64, max_vel: f64, max_accel: f64) -> f64 {
+    if length <= 0.0 {
+        return 0.0;
+    }
+    // The highest speed reached in the segment, if there is no time to cruise at `max_vel`.
+    let peak = ((2.0 * max_accel * length + v0 * v0 + v1 * v1) / 2.0)
+        .sqrt()
+        .min(max_vel);
+    let accel_length = (peak * peak - v0 * v0) / (2.0 * max_accel);
+    let decel_length = (peak * peak - v1 * v1) / (2.0 * max_accel);
+    let cruise_length = (length - accel_length - decel_length).max(0.0);
+    (peak - v0) / max_accel + (peak - v1) / max_accel + cruise_length / peak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_straight_trapezoid() {
+        let path = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(10.0, 0.0),
+        ];
+        let timed = parameterize(&path, 2.0, 1.0).unwrap();
+
+        assert_eq!(timed.len(), 3);
+        assert_eq!(timed[0].time, 0.0);
+        assert_eq!(timed[0].speed, 0.0);
+        // Still accelerating 1 m in: v = sqrt(2ad) and t = v / a.
+        assert!((timed[1].speed - 2.0f64.sqrt()).abs() < 1e-9);
+        assert!((timed[1].time - 2.0f64.sqrt()).abs() < 1e-9);
+        // 2 s to accelerate over 2 m, 3 s to cruise 6 m, then 2 s to stop over 2 m.
+        assert!((timed[2].time - 7.0).abs() < 1e-9);
+        assert_eq!(timed[2].speed, 0.0);
+    }
+
+    #[test]
+    fn test_corner_slows_down() {
+        let path = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 0.0),
+            Vector2::new(10.0, 10.0),
+        ];
+        let timed = parameterize(&path, 2.0, 1.0).unwrap();
+        assert!((timed[1].speed - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_limits() {
+        let path = [Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)];
+        assert!(parameterize(&path, 0.0, 1.0).is_none());
+        assert!(parameterize(&path, 1.0, -1.0).is_none());
+        assert!(parameterize(&path, f64::INFINITY, 1.0).is_none());
+        assert!(parameterize(&[Vector2::new(f64::NAN, 0.0)], 1.0, 1.0).is_none());
+    }
+}
@@ -1,6 +1,14 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
 use crate::{
-    Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior, FallibleStatus,
-    InfallibleBehavior, InfallibleStatus, IntoRon, Status,
+    node_field, node_ron, Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior,
+    FallibleStatus, InfallibleBehavior, InfallibleStatus, IntoRon, Status,
 };
 
 impl<T, F: FnMut(&mut B) -> Status<T>, B> Behavior<B, T> for F {
@@ -44,7 +52,7 @@ impl<T, B> InfallibleBehavior<B, T> for AlwaysSucceed {
 
 impl IntoRon for AlwaysSucceed {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::String("AlwaysSucceed".to_string())
+        node_ron("AlwaysSucceed", [])
     }
 }
 
@@ -69,7 +77,7 @@ impl<T, B> FallibleBehavior<B, T> for AlwaysFail {
 
 impl IntoRon for AlwaysFail {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::String("AlwaysFail".to_string())
+        node_ron("AlwaysFail", [])
     }
 }
 
@@ -110,7 +118,7 @@ impl CancelSafe for AlwaysRunning {
 
 impl IntoRon for AlwaysRunning {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::String("AlwaysRunning".to_string())
+        node_ron("AlwaysRunning", [])
     }
 }
 
@@ -154,3 +162,199 @@ impl<F> CancelSafe for RunOnce<F> {
         self.ran = false;
     }
 }
+
+/// An action with hooks for when it starts and stops running.
+///
+/// `on_enter` is called on the first tick after the action was created, completed,
+/// or reset. `on_tick` is called on every tick, including the first. `on_exit` is
+/// called once the action succeeds or fails, and when the action is reset while it
+/// is running, so any resource acquired in `on_enter` can be released in `on_exit`.
+///
+/// `on_exit` does not receive the blackboard, as `CancelSafe::reset` does not have access to it.
+pub struct StatefulAction<E, K, X> {
+    pub on_enter: E,
+    pub on_tick: K,
+    pub on_exit: X,
+    entered: bool,
+}
+
+impl<E, K, X> StatefulAction<E, K, X> {
+    pub fn new(on_enter: E, on_tick: K, on_exit: X) -> Self {
+        Self {
+            on_enter,
+            on_tick,
+            on_exit,
+            entered: false,
+        }
+    }
+}
+
+impl<B, T, E, K, X> Behavior<B, T> for StatefulAction<E, K, X>
+where
+    E: FnMut(&mut B),
+    K: FnMut(&mut B) -> Status<T>,
+    X: FnMut(),
+{
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        if !self.entered {
+            (self.on_enter)(blackboard);
+            self.entered = true;
+        }
+        let status = (self.on_tick)(blackboard);
+        if !status.is_running() {
+            self.entered = false;
+            (self.on_exit)();
+        }
+        status
+    }
+}
+
+impl<E, K, X: FnMut()> CancelSafe for StatefulAction<E, K, X> {
+    fn reset(&mut self) {
+        if self.entered {
+            self.entered = false;
+            (self.on_exit)();
+        }
+    }
+}
+
+/// An action that runs a future to completion.
+///
+/// On the first tick after the action was created, completed, or reset, `make_future`
+/// is called to create the future. The future is then polled once per tick, without
+/// busy-waiting, returning `Running` while it is pending, and `Success` or `Failure`
+/// depending on the `bool` it resolves to. Polling uses a waker that does nothing, so
+/// the future will only make progress when the tree is ticked.
+///
+/// Resetting the action drops the future, so the next tick starts over.
+pub struct AsyncBehavior<F, Fut> {
+    pub make_future: F,
+    future: Option<Pin<Box<Fut>>>,
+    waker: Waker,
+}
+
+impl<F, Fut> AsyncBehavior<F, Fut> {
+    pub fn new(make_future: F) -> Self {
+        Self {
+            make_future,
+            future: None,
+            waker: Waker::from(Arc::new(NoopWaker)),
+        }
+    }
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+impl<B, T, F, Fut> Behavior<B, T> for AsyncBehavior<F, Fut>
+where
+    T: Default,
+    F: FnMut(&mut B) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        let future = self
+            .future
+            .get_or_insert_with(|| Box::pin((self.make_future)(blackboard)));
+        match future.as_mut().poll(&mut Context::from_waker(&self.waker)) {
+            Poll::Pending => Status::Running(T::default()),
+            Poll::Ready(ok) => {
+                self.future = None;
+                ok.into()
+            }
+        }
+    }
+}
+
+impl<F, Fut> CancelSafe for AsyncBehavior<F, Fut> {
+    fn reset(&mut self) {
+        self.future = None;
+    }
+}
+
+/// A leaf that checks a condition on the blackboard.
+///
+/// Returns `Success` if `check` returns `true`, and `Failure` otherwise. Unlike a closure,
+/// a `Condition` has a `name`, so it can be serialized with `IntoRon` and read back with a
+/// `ConditionRegistry`.
+pub struct Condition<F> {
+    pub name: String,
+    pub check: F,
+}
+
+impl<F> Condition<F> {
+    pub fn new(name: impl Into<String>, check: F) -> Self {
+        Self {
+            name: name.into(),
+            check,
+        }
+    }
+}
+
+impl<B, T, F: Fn(&B) -> bool> Behavior<B, T> for Condition<F> {
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        (self.check)(blackboard).into()
+    }
+}
+
+impl<F> CancelSafe for Condition<F> {
+    fn reset(&mut self) {}
+}
+
+impl<F> IntoRon for Condition<F> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "Condition",
+            [("name", ron::Value::String(self.name.clone()))],
+        )
+    }
+}
+
+type Predicate<B> = Arc<dyn Fn(&B) -> bool>;
+
+/// A set of named predicates that `Condition`s can be created from.
+pub struct ConditionRegistry<B> {
+    predicates: HashMap<String, Predicate<B>>,
+}
+
+impl<B> Default for ConditionRegistry<B> {
+    fn default() -> Self {
+        Self {
+            predicates: HashMap::new(),
+        }
+    }
+}
+
+impl<B> ConditionRegistry<B> {
+    /// Registers `check` under `name`, replacing any predicate with the same name.
+    pub fn register(&mut self, name: impl Into<String>, check: impl Fn(&B) -> bool + 'static) {
+        self.predicates.insert(name.into(), Arc::new(check));
+    }
+
+    /// Creates a `Condition` using the predicate registered under `name`.
+    pub fn condition(&self, name: &str) -> Option<Condition<impl Fn(&B) -> bool>> {
+        let check = self.predicates.get(name)?.clone();
+        Some(Condition::new(name, move |blackboard: &B| {
+            check(blackboard)
+        }))
+    }
+
+    /// Creates a `Condition` from the value produced by its `IntoRon` implementation.
+    ///
+    /// Returns `None` if `value` is not a `Condition`, or if its name was not registered.
+    pub fn from_ron(&self, value: &ron::Value) -> Option<Condition<impl Fn(&B) -> bool>> {
+        let ron::Value::Map(map) = value else {
+            return None;
+        };
+        if !matches!(node_field(map, "type"), Some(ron::Value::String(t)) if t == "Condition") {
+            return None;
+        }
+        let Some(ron::Value::String(name)) = node_field(map, "name") else {
+            return None;
+        };
+        self.condition(name)
+    }
+}
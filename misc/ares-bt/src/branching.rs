@@ -1,6 +1,6 @@
 use crate::{
-    Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior, FallibleStatus,
-    InfallibleBehavior, InfallibleStatus, IntoRon, Status,
+    node_ron, Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior,
+    FallibleStatus, InfallibleBehavior, InfallibleStatus, IntoRon, Observer, Status,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,23 +24,13 @@ where
     C: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
+        node_ron(
+            "IfElse",
             [
-                (
-                    ron::Value::String("condition".to_string()),
-                    self.condition.into_ron(),
-                ),
-                (
-                    ron::Value::String("success".to_string()),
-                    self.if_true.into_ron(),
-                ),
-                (
-                    ron::Value::String("failure".to_string()),
-                    self.if_false.into_ron(),
-                ),
-            ]
-            .into_iter()
-            .collect(),
+                ("condition", self.condition.into_ron()),
+                ("success", self.if_true.into_ron()),
+                ("failure", self.if_false.into_ron()),
+            ],
         )
     }
 }
@@ -52,26 +42,33 @@ where
     C: Behavior<D, T>,
 {
     fn run(&mut self, blackboard: &mut D) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut D, observer: &mut Observer) -> Status<T> {
         let result = match self.state {
-            IfElseState::Condition => match self.condition.run(blackboard) {
-                Status::Running(t) => return Status::Running(t),
-                Status::Success => {
-                    self.state = IfElseState::IfTrue;
-                    self.if_true.run(blackboard)
+            IfElseState::Condition => {
+                match observer.run_child(0, &mut self.condition, blackboard) {
+                    Status::Running(t) => Status::Running(t),
+                    Status::Success => {
+                        self.state = IfElseState::IfTrue;
+                        observer.run_child(1, &mut self.if_true, blackboard)
+                    }
+                    Status::Failure => {
+                        self.state = IfElseState::IfFalse;
+                        observer.run_child(2, &mut self.if_false, blackboard)
+                    }
                 }
-                Status::Failure => {
-                    self.state = IfElseState::IfFalse;
-                    self.if_false.run(blackboard)
-                }
-            },
-            IfElseState::IfTrue => self.if_true.run(blackboard),
-            IfElseState::IfFalse => self.if_false.run(blackboard),
+            }
+            IfElseState::IfTrue => observer.run_child(1, &mut self.if_true, blackboard),
+            IfElseState::IfFalse => observer.run_child(2, &mut self.if_false, blackboard),
         };
 
         if !result.is_running() {
             self.state = IfElseState::Condition;
         }
 
+        observer.report("if_else", result.node_status());
         result
     }
 }
@@ -200,19 +197,12 @@ where
     B: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
+        node_ron(
+            "TryCatch",
             [
-                (
-                    ron::Value::String("try".to_string()),
-                    self.try_behavior.into_ron(),
-                ),
-                (
-                    ron::Value::String("catch".to_string()),
-                    self.catch.into_ron(),
-                ),
-            ]
-            .into_iter()
-            .collect(),
+                ("try", self.try_behavior.into_ron()),
+                ("catch", self.catch.into_ron()),
+            ],
         )
     }
 }
@@ -223,23 +213,27 @@ where
     B: Behavior<D, T>,
 {
     fn run(&mut self, blackboard: &mut D) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut D, observer: &mut Observer) -> Status<T> {
         let result = if self.trying {
-            match self.try_behavior.run(blackboard) {
-                Status::Running(t) => return Status::Running(t),
-                Status::Success => return Status::Success,
+            match observer.run_child(0, &mut self.try_behavior, blackboard) {
                 Status::Failure => {
                     self.trying = false;
-                    self.catch.run(blackboard)
+                    observer.run_child(1, &mut self.catch, blackboard)
                 }
+                status => status,
             }
         } else {
-            self.catch.run(blackboard)
+            observer.run_child(1, &mut self.catch, blackboard)
         };
 
         if !result.is_running() {
             self.trying = true;
         }
 
+        observer.report("try_catch", result.node_status());
         result
     }
 }
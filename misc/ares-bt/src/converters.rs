@@ -1,8 +1,8 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cell::Cell, marker::PhantomData, rc::Rc};
 
 use crate::{
-    Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior, FallibleStatus,
-    InfallibleBehavior, InfallibleStatus, IntoRon, Status,
+    node_ron, Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior,
+    FallibleStatus, InfallibleBehavior, InfallibleStatus, IntoRon, Observer, Status,
 };
 
 pub struct InfallibleShim<A>(pub A);
@@ -12,10 +12,18 @@ where
     A: InfallibleBehavior<B, T>,
 {
     fn run(&mut self, blackboard: &mut B) -> Status<T> {
-        match self.0.run_infallible(blackboard) {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    /// The child is reported, but not its descendants, as it is not a `Behavior`.
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = match self.0.run_infallible(blackboard) {
             InfallibleStatus::Running(t) => Status::Running(t),
             InfallibleStatus::Success => Status::Success,
-        }
+        };
+        observer.report_child(0, std::any::type_name::<A>(), status.node_status());
+        observer.report("infallible", status.node_status());
+        status
     }
 }
 
@@ -24,14 +32,7 @@ where
     A: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
-            [(
-                ron::Value::String("infallible".to_string()),
-                self.0.into_ron(),
-            )]
-            .into_iter()
-            .collect(),
-        )
+        node_ron("Infallible", [("child", self.0.into_ron())])
     }
 }
 
@@ -51,10 +52,18 @@ where
     A: FallibleBehavior<B, T>,
 {
     fn run(&mut self, blackboard: &mut B) -> Status<T> {
-        match self.0.run_fallible(blackboard) {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    /// The child is reported, but not its descendants, as it is not a `Behavior`.
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = match self.0.run_fallible(blackboard) {
             FallibleStatus::Running(t) => Status::Running(t),
             FallibleStatus::Failure => Status::Failure,
-        }
+        };
+        observer.report_child(0, std::any::type_name::<A>(), status.node_status());
+        observer.report("fallible", status.node_status());
+        status
     }
 }
 
@@ -72,14 +81,7 @@ where
     A: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
-            [(
-                ron::Value::String("fallible".to_string()),
-                self.0.into_ron(),
-            )]
-            .into_iter()
-            .collect(),
-        )
+        node_ron("Fallible", [("child", self.0.into_ron())])
     }
 }
 
@@ -90,7 +92,15 @@ where
     A: EternalBehavior<B, T>,
 {
     fn run(&mut self, blackboard: &mut B) -> Status<T> {
-        Status::Running(self.0.run_eternal(blackboard).unwrap())
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    /// The child is reported, but not its descendants, as it is not a `Behavior`.
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = Status::Running(self.0.run_eternal(blackboard).unwrap());
+        observer.report_child(0, std::any::type_name::<A>(), status.node_status());
+        observer.report("eternal", status.node_status());
+        status
     }
 }
 
@@ -108,11 +118,7 @@ where
     A: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
-            [(ron::Value::String("eternal".to_string()), self.0.into_ron())]
-                .into_iter()
-                .collect(),
-        )
+        node_ron("Eternal", [("child", self.0.into_ron())])
     }
 }
 
@@ -123,11 +129,17 @@ where
     A: Behavior<B, T>,
 {
     fn run(&mut self, blackboard: &mut B) -> Status<T> {
-        match self.0.run(blackboard) {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = match observer.run_child(0, &mut self.0, blackboard) {
             Status::Failure => Status::Success,
             Status::Success => Status::Failure,
             Status::Running(t) => Status::Running(t),
-        }
+        };
+        observer.report("invert", status.node_status());
+        status
     }
 }
 
@@ -145,11 +157,7 @@ where
     A: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
-            [(ron::Value::String("invert".to_string()), self.0.into_ron())]
-                .into_iter()
-                .collect(),
-        )
+        node_ron("Invert", [("child", self.0.into_ron())])
     }
 }
 
@@ -193,10 +201,23 @@ where
     A: Behavior<B, T>,
 {
     fn run(&mut self, blackboard: &mut B) -> Status<T> {
-        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.0.run(blackboard))) {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let depth = observer.path.len();
+        let status = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            observer.run_child(0, &mut self.0, blackboard)
+        })) {
             Ok(status) => status,
-            Err(_) => Status::Failure,
-        }
+            Err(_) => {
+                // The descendant that panicked did not get to leave the path.
+                observer.path.truncate(depth);
+                Status::Failure
+            }
+        };
+        observer.report("catch_panic", status.node_status());
+        status
     }
 }
 
@@ -228,14 +249,7 @@ where
     A: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
-            [(
-                ron::Value::String("catch_panic".to_string()),
-                self.0.into_ron(),
-            )]
-            .into_iter()
-            .collect(),
-        )
+        node_ron("CatchPanic", [("child", self.0.into_ron())])
     }
 }
 
@@ -253,9 +267,333 @@ impl<A> Rename<A> {
     }
 }
 
-impl<A> IntoRon for Rename<A> {
+impl<A, B, T> Behavior<B, T> for Rename<A>
+where
+    A: Behavior<B, T>,
+{
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = observer.run_child(0, &mut self.behavior, blackboard);
+        observer.report(&self.name, status.node_status());
+        status
+    }
+}
+
+impl<A> CancelSafe for Rename<A>
+where
+    A: CancelSafe,
+{
+    fn reset(&mut self) {
+        self.behavior.reset();
+    }
+}
+
+impl<A> IntoRon for Rename<A>
+where
+    A: IntoRon,
+{
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "Rename",
+            [
+                ("name", ron::Value::String(self.name.to_string())),
+                ("child", self.behavior.into_ron()),
+            ],
+        )
+    }
+}
+
+/// Runs a behavior on a part of the blackboard.
+///
+/// The `lens` projects the blackboard of the parent into the blackboard of the
+/// child, so a subtree written against a small blackboard (such as just the pose)
+/// can be composed into a tree with a bigger one.
+pub struct Subtree<B, SB, C, L> {
+    pub lens: L,
+    pub child: C,
+    _phantom: PhantomData<fn(&mut B) -> &mut SB>,
+}
+
+impl<B, SB, C, L> Subtree<B, SB, C, L>
+where
+    L: Fn(&mut B) -> &mut SB,
+{
+    pub fn new(lens: L, child: C) -> Self {
+        Self {
+            lens,
+            child,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<B, SB, C, L, T> Behavior<B, T> for Subtree<B, SB, C, L>
+where
+    C: Behavior<SB, T>,
+    L: Fn(&mut B) -> &mut SB,
+{
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = observer.run_child(0, &mut self.child, (self.lens)(blackboard));
+        observer.report("subtree", status.node_status());
+        status
+    }
+}
+
+impl<B, SB, C, L, T> InfallibleBehavior<B, T> for Subtree<B, SB, C, L>
+where
+    C: InfallibleBehavior<SB, T>,
+    L: Fn(&mut B) -> &mut SB,
+{
+    fn run_infallible(&mut self, blackboard: &mut B) -> InfallibleStatus<T> {
+        self.child.run_infallible((self.lens)(blackboard))
+    }
+}
+
+impl<B, SB, C, L, T> FallibleBehavior<B, T> for Subtree<B, SB, C, L>
+where
+    C: FallibleBehavior<SB, T>,
+    L: Fn(&mut B) -> &mut SB,
+{
+    fn run_fallible(&mut self, blackboard: &mut B) -> FallibleStatus<T> {
+        self.child.run_fallible((self.lens)(blackboard))
+    }
+}
+
+impl<B, SB, C, L, T> EternalBehavior<B, T> for Subtree<B, SB, C, L>
+where
+    C: EternalBehavior<SB, T>,
+    L: Fn(&mut B) -> &mut SB,
+{
+    fn run_eternal(&mut self, blackboard: &mut B) -> EternalStatus<T> {
+        self.child.run_eternal((self.lens)(blackboard))
+    }
+}
+
+impl<B, SB, C, L> CancelSafe for Subtree<B, SB, C, L>
+where
+    C: CancelSafe,
+{
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+impl<B, SB, C, L> IntoRon for Subtree<B, SB, C, L>
+where
+    C: IntoRon,
+{
+    fn into_ron(&self) -> ron::Value {
+        node_ron("Subtree", [("child", self.child.into_ron())])
+    }
+}
+
+/// Only runs its child while it holds a shared resource.
+///
+/// The resource is acquired on the first tick, and released once the child succeeds
+/// or fails, or when this is reset. If another `ResourceGuard` sharing the same
+/// `token` holds the resource, this fails without running the child.
+pub struct ResourceGuard<C> {
+    pub child: C,
+    token: Rc<Cell<bool>>,
+    held: bool,
+}
+
+impl<C> ResourceGuard<C> {
+    /// Creates a guard over the resource represented by `token`.
+    ///
+    /// `token` is `true` while the resource is held, so it should start as `false`.
+    pub fn new(token: Rc<Cell<bool>>, child: C) -> Self {
+        Self {
+            child,
+            token,
+            held: false,
+        }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        if !self.held {
+            if self.token.get() {
+                return false;
+            }
+            self.token.set(true);
+            self.held = true;
+        }
+        true
+    }
+
+    fn release(&mut self) {
+        if self.held {
+            self.token.set(false);
+            self.held = false;
+        }
+    }
+}
+
+impl<B, T, C> Behavior<B, T> for ResourceGuard<C>
+where
+    C: Behavior<B, T>,
+{
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = if self.try_acquire() {
+            let status = observer.run_child(0, &mut self.child, blackboard);
+            if !status.is_running() {
+                self.release();
+            }
+            status
+        } else {
+            Status::Failure
+        };
+        observer.report("resource_guard", status.node_status());
+        status
+    }
+}
+
+impl<B, T, C> FallibleBehavior<B, T> for ResourceGuard<C>
+where
+    C: FallibleBehavior<B, T>,
+{
+    fn run_fallible(&mut self, blackboard: &mut B) -> FallibleStatus<T> {
+        if !self.try_acquire() {
+            return FallibleStatus::Failure;
+        }
+        let status = self.child.run_fallible(blackboard);
+        if !status.is_running() {
+            self.release();
+        }
+        status
+    }
+}
+
+impl<C> CancelSafe for ResourceGuard<C>
+where
+    C: CancelSafe,
+{
+    fn reset(&mut self) {
+        self.child.reset();
+        self.release();
+    }
+}
+
+impl<C> IntoRon for ResourceGuard<C>
+where
+    C: IntoRon,
+{
+    fn into_ron(&self) -> ron::Value {
+        node_ron("ResourceGuard", [("child", self.child.into_ron())])
+    }
+}
+
+/// Fails once its child has been ticked `max_ticks` times.
+///
+/// Unlike a timeout, this bounds the number of ticks instead of the time spent. Every tick
+/// of the child is counted, whatever its status, so a child that keeps finishing and
+/// restarting still runs out of ticks. The `max_ticks`th tick returns `Failure` and resets
+/// the child, and later ticks fail without ticking the child until this is reset.
+pub struct TickLimit<C> {
+    pub child: C,
+    pub max_ticks: usize,
+    ticks: usize,
+}
+
+impl<C> TickLimit<C> {
+    pub fn new(max_ticks: usize, child: C) -> Self {
+        Self {
+            child,
+            max_ticks,
+            ticks: 0,
+        }
+    }
+
+    /// Returns `true` if every tick has been used.
+    fn exhausted(&self) -> bool {
+        self.ticks >= self.max_ticks
+    }
+
+    /// Counts a tick of the child, returning `true` if that used the last tick.
+    fn count_tick(&mut self) -> bool {
+        self.ticks += 1;
+        self.exhausted()
+    }
+}
+
+impl<B, T, C> Behavior<B, T> for TickLimit<C>
+where
+    C: Behavior<B, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = if self.exhausted() {
+            Status::Failure
+        } else {
+            let status = observer.run_child(0, &mut self.child, blackboard);
+            if self.count_tick() {
+                self.child.reset();
+                Status::Failure
+            } else {
+                status
+            }
+        };
+        observer.report("tick_limit", status.node_status());
+        status
+    }
+}
+
+impl<B, T, C> FallibleBehavior<B, T> for TickLimit<C>
+where
+    C: FallibleBehavior<B, T> + CancelSafe,
+{
+    fn run_fallible(&mut self, blackboard: &mut B) -> FallibleStatus<T> {
+        if self.exhausted() {
+            return FallibleStatus::Failure;
+        }
+        let status = self.child.run_fallible(blackboard);
+        if self.count_tick() {
+            self.child.reset();
+            return FallibleStatus::Failure;
+        }
+        status
+    }
+}
+
+impl<C> CancelSafe for TickLimit<C>
+where
+    C: CancelSafe,
+{
+    fn reset(&mut self) {
+        self.ticks = 0;
+        self.child.reset();
+    }
+}
+
+impl<C> IntoRon for TickLimit<C>
+where
+    C: IntoRon,
+{
     fn into_ron(&self) -> ron::Value {
-        ron::Value::String(self.name.to_string())
+        node_ron(
+            "TickLimit",
+            [
+                (
+                    "max_ticks",
+                    ron::Value::Number(ron::Number::new(self.max_ticks as i64)),
+                ),
+                ("child", self.child.into_ron()),
+            ],
+        )
     }
 }
 
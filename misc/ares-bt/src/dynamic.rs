@@ -0,0 +1,125 @@
+use crate::{
+    action::{AlwaysFail, AlwaysRunning, AlwaysSucceed, ConditionRegistry},
+    branching::{IfElse, TryCatch},
+    converters::{CatchPanic, Invert, Rename, TickLimit},
+    looping::WhileLoop,
+    node_field,
+    sequence::{
+        ParallelAny, ParallelSelect, ParallelSequence, Race, ReactiveSelect, ReactiveSequence,
+        Select, Sequence,
+    },
+    Behavior, CancelSafe, IntoRon, Observer, Status,
+};
+
+/// A node that can be stored in a `DynBehavior`.
+pub trait DynNode<B, T>: Behavior<B, T> + CancelSafe + IntoRon {}
+
+impl<B, T, N> DynNode<B, T> for N where N: Behavior<B, T> + CancelSafe + IntoRon {}
+
+/// A behavior tree whose shape is only known at runtime, such as one read back with
+/// `ConditionRegistry::tree_from_ron`.
+///
+/// Composites of `DynBehavior`s use a `Vec` for their children instead of a tuple.
+pub struct DynBehavior<B, T>(pub Box<dyn DynNode<B, T>>);
+
+impl<B, T> DynBehavior<B, T> {
+    pub fn new(node: impl DynNode<B, T> + 'static) -> Self {
+        Self(Box::new(node))
+    }
+}
+
+impl<B, T> Behavior<B, T> for DynBehavior<B, T> {
+    fn run(&mut self, blackboard: &mut B) -> Status<T> {
+        self.0.run(blackboard)
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        self.0.run_with_observer(blackboard, observer)
+    }
+}
+
+impl<B, T> CancelSafe for DynBehavior<B, T> {
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+impl<B, T> IntoRon for DynBehavior<B, T> {
+    fn into_ron(&self) -> ron::Value {
+        self.0.into_ron()
+    }
+}
+
+impl<B: 'static> ConditionRegistry<B> {
+    /// Reads back a tree from the value produced by its `IntoRon` implementation.
+    ///
+    /// `Condition`s are looked up in this registry. Returns `None` if `value` does not follow
+    /// the format described by `IntoRon`, if a composite has no children, if a condition was
+    /// not registered, or if the tree has a node that cannot be read back. Only the nodes that
+    /// are fully described by their `IntoRon` output can be read back, so shims, `Subtree` and
+    /// `ResourceGuard` cannot.
+    pub fn tree_from_ron<T: Default + 'static>(
+        &self,
+        value: &ron::Value,
+    ) -> Option<DynBehavior<B, T>> {
+        let ron::Value::Map(map) = value else {
+            return None;
+        };
+        let field = |key: &str| node_field(map, key);
+        let child = |key: &str| self.tree_from_ron::<T>(field(key)?);
+        let children = || {
+            let Some(ron::Value::Seq(values)) = field("children") else {
+                return None;
+            };
+            if values.is_empty() {
+                return None;
+            }
+            values
+                .iter()
+                .map(|value| self.tree_from_ron::<T>(value))
+                .collect::<Option<Vec<_>>>()
+        };
+        let Some(ron::Value::String(node_type)) = field("type") else {
+            return None;
+        };
+
+        let tree = match node_type.as_str() {
+            "AlwaysSucceed" => DynBehavior::new(AlwaysSucceed),
+            "AlwaysFail" => DynBehavior::new(AlwaysFail),
+            "AlwaysRunning" => DynBehavior::new(AlwaysRunning),
+            "Condition" => DynBehavior::new(self.from_ron(value)?),
+            "Sequence" => DynBehavior::new(Sequence::new(children()?)),
+            "ReactiveSequence" => DynBehavior::new(ReactiveSequence::new(children()?)),
+            "Select" => DynBehavior::new(Select::new(children()?)),
+            "ReactiveSelect" => DynBehavior::new(ReactiveSelect::new(children()?)),
+            "ParallelSequence" => DynBehavior::new(ParallelSequence::new(children()?)),
+            "ParallelSelect" => DynBehavior::new(ParallelSelect::new(children()?)),
+            "ParallelAny" => DynBehavior::new(ParallelAny::new(children()?)),
+            "Race" => DynBehavior::new(Race::new(children()?)),
+            "IfElse" => DynBehavior::new(IfElse::new(
+                child("condition")?,
+                child("success")?,
+                child("failure")?,
+            )),
+            "TryCatch" => DynBehavior::new(TryCatch::new(child("try")?, child("catch")?)),
+            "WhileLoop" => DynBehavior::new(WhileLoop::new(child("condition")?, child("body")?)),
+            "Invert" => DynBehavior::new(Invert(child("child")?)),
+            "CatchPanic" => DynBehavior::new(CatchPanic(child("child")?)),
+            "TickLimit" => {
+                let Some(ron::Value::Number(max_ticks)) = field("max_ticks") else {
+                    return None;
+                };
+                let max_ticks = usize::try_from(max_ticks.as_i64()?).ok()?;
+                DynBehavior::new(TickLimit::new(max_ticks, child("child")?))
+            }
+            "Rename" => {
+                let Some(ron::Value::String(name)) = field("name") else {
+                    return None;
+                };
+                DynBehavior::new(Rename::new(name.clone(), child("child")?))
+            }
+            _ => return None,
+        };
+        Some(tree)
+    }
+}
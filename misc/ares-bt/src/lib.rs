@@ -1,8 +1,11 @@
 #![feature(unboxed_closures, fn_traits)]
 
+use std::collections::HashMap;
+
 pub mod action;
 pub mod branching;
 pub mod converters;
+pub mod dynamic;
 pub mod looping;
 pub mod sequence;
 
@@ -37,6 +40,42 @@ impl<T> Status<T> {
             Self::Failure => false,
         }
     }
+
+    /// Gets the variant of this status, without the value carried by `Running`.
+    pub const fn node_status(&self) -> NodeStatus {
+        match self {
+            Self::Running(_) => NodeStatus::Running,
+            Self::Success => NodeStatus::Success,
+            Self::Failure => NodeStatus::Failure,
+        }
+    }
+
+    /// Transforms the value carried by `Running`, leaving the other variants untouched.
+    pub fn map_running<U>(self, f: impl FnOnce(T) -> U) -> Status<U> {
+        match self {
+            Self::Running(t) => Status::Running(f(t)),
+            Self::Success => Status::Success,
+            Self::Failure => Status::Failure,
+        }
+    }
+
+    /// Converts into an `InfallibleStatus`, or returns `None` if this is `Failure`.
+    pub fn into_infallible(self) -> Option<InfallibleStatus<T>> {
+        match self {
+            Self::Running(t) => Some(InfallibleStatus::Running(t)),
+            Self::Success => Some(InfallibleStatus::Success),
+            Self::Failure => None,
+        }
+    }
+
+    /// Converts into a `FallibleStatus`, or returns `None` if this is `Success`.
+    pub fn into_fallible(self) -> Option<FallibleStatus<T>> {
+        match self {
+            Self::Running(t) => Some(FallibleStatus::Running(t)),
+            Self::Success => None,
+            Self::Failure => Some(FallibleStatus::Failure),
+        }
+    }
 }
 
 impl<T> From<bool> for Status<T> {
@@ -75,6 +114,27 @@ impl<T> FallibleStatus<T> {
             Self::Failure => false,
         }
     }
+
+    /// Transforms the value carried by `Running`, leaving `Failure` untouched.
+    pub fn map_running<U>(self, f: impl FnOnce(T) -> U) -> FallibleStatus<U> {
+        match self {
+            Self::Running(t) => FallibleStatus::Running(f(t)),
+            Self::Failure => FallibleStatus::Failure,
+        }
+    }
+
+    /// Converts into a `Status`.
+    pub fn into_status(self) -> Status<T> {
+        self.into()
+    }
+
+    /// Converts into an `InfallibleStatus`, or returns `None` if this is `Failure`.
+    pub fn into_infallible(self) -> Option<InfallibleStatus<T>> {
+        match self {
+            Self::Running(t) => Some(InfallibleStatus::Running(t)),
+            Self::Failure => None,
+        }
+    }
 }
 
 pub enum InfallibleStatus<T> {
@@ -103,6 +163,27 @@ impl<T> InfallibleStatus<T> {
             Self::Success => false,
         }
     }
+
+    /// Transforms the value carried by `Running`, leaving `Success` untouched.
+    pub fn map_running<U>(self, f: impl FnOnce(T) -> U) -> InfallibleStatus<U> {
+        match self {
+            Self::Running(t) => InfallibleStatus::Running(f(t)),
+            Self::Success => InfallibleStatus::Success,
+        }
+    }
+
+    /// Converts into a `Status`.
+    pub fn into_status(self) -> Status<T> {
+        self.into()
+    }
+
+    /// Converts into a `FallibleStatus`, or returns `None` if this is `Success`.
+    pub fn into_fallible(self) -> Option<FallibleStatus<T>> {
+        match self {
+            Self::Running(t) => Some(FallibleStatus::Running(t)),
+            Self::Success => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -135,6 +216,18 @@ impl<T> EternalStatus<T> {
         }
     }
 
+    /// Transforms the value carried by `Running`.
+    pub fn map_running<U>(self, f: impl FnOnce(T) -> U) -> EternalStatus<U> {
+        match self {
+            Self::Running(t) => EternalStatus::Running(f(t)),
+        }
+    }
+
+    /// Converts into a `Status`.
+    pub fn into_status(self) -> Status<T> {
+        self.into()
+    }
+
     pub fn unwrap(self) -> T {
         match self {
             Self::Running(t) => t,
@@ -148,9 +241,130 @@ impl<T> From<T> for EternalStatus<T> {
     }
 }
 
+/// The variant of a `Status`, without the value carried by `Running`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeStatus {
+    Running,
+    Success,
+    Failure,
+}
+
+/// A change in the status of a node, reported by an `Observer`.
+#[derive(Clone, Copy, Debug)]
+pub struct NodeEvent<'a> {
+    /// The name of the node.
+    ///
+    /// Composites and decorators use a snake_case name, such as `"sequence"`, and `Rename`
+    /// uses its `name`. Other nodes use their type name.
+    pub name: &'a str,
+    /// The index of each child taken to reach this node from the root of the tree.
+    ///
+    /// This is empty for the root.
+    pub path: &'a [usize],
+    /// The status of the node the last time it was ticked, or `None` if this is the first tick.
+    pub previous: Option<NodeStatus>,
+    pub status: NodeStatus,
+}
+
+type OnTransition<'a> = Box<dyn FnMut(NodeEvent) + 'a>;
+
+/// Reports changes in the status of the nodes in a tree, for use with `Behavior::run_with_observer`.
+///
+/// A node is reported the first time it is ticked, and then whenever its status differs from
+/// the last time it was ticked, so the same `Observer` should be passed to every tick.
+pub struct Observer<'a> {
+    on_transition: Option<OnTransition<'a>>,
+    path: Vec<usize>,
+    statuses: HashMap<Vec<usize>, NodeStatus>,
+}
+
+impl<'a> Observer<'a> {
+    pub fn new(on_transition: impl FnMut(NodeEvent) + 'a) -> Self {
+        Self {
+            on_transition: Some(Box::new(on_transition)),
+            path: Vec::new(),
+            statuses: HashMap::new(),
+        }
+    }
+
+    /// An `Observer` that reports nothing. This is what `Behavior::run` uses.
+    pub fn disabled() -> Self {
+        Self {
+            on_transition: None,
+            path: Vec::new(),
+            statuses: HashMap::new(),
+        }
+    }
+
+    /// Ticks `child`, which is the child at `index` of the node currently being ticked.
+    pub fn run_child<B, T, C>(
+        &mut self,
+        index: usize,
+        child: &mut C,
+        blackboard: &mut B,
+    ) -> Status<T>
+    where
+        C: Behavior<B, T> + ?Sized,
+    {
+        if self.on_transition.is_none() {
+            return child.run_with_observer(blackboard, self);
+        }
+        self.path.push(index);
+        let status = child.run_with_observer(blackboard, self);
+        self.path.pop();
+        status
+    }
+
+    /// Reports the status of the node currently being ticked.
+    ///
+    /// Composites and decorators should call this once per tick, after ticking their children.
+    pub fn report(&mut self, name: &str, status: NodeStatus) {
+        let Some(on_transition) = &mut self.on_transition else {
+            return;
+        };
+        let previous = self.statuses.get(self.path.as_slice()).copied();
+        if previous == Some(status) {
+            return;
+        }
+        self.statuses.insert(self.path.clone(), status);
+        on_transition(NodeEvent {
+            name,
+            path: &self.path,
+            previous,
+            status,
+        });
+    }
+
+    /// Reports the status of the child at `index` of the node currently being ticked.
+    ///
+    /// This is for children that were not ticked through `run_child`, such as those that
+    /// only implement `InfallibleBehavior`, `FallibleBehavior` or `EternalBehavior`.
+    pub fn report_child(&mut self, index: usize, name: &str, status: NodeStatus) {
+        if self.on_transition.is_none() {
+            return;
+        }
+        self.path.push(index);
+        self.report(name, status);
+        self.path.pop();
+    }
+}
+
 /// A behavior that runs until it fails or succeeds.
 pub trait Behavior<B, T> {
     fn run(&mut self, blackboard: &mut B) -> Status<T>;
+
+    /// The same as `run`, except that `observer` is told about every node in this tree whose
+    /// status changed.
+    ///
+    /// Children are reported before their parents. Composites and decorators override this
+    /// to tick their children through `Observer::run_child` and report themselves, and
+    /// implement `run` by calling this with `Observer::disabled`. All other nodes only report
+    /// themselves.
+    fn run_with_observer(&mut self, blackboard: &mut B, observer: &mut Observer) -> Status<T> {
+        let status = self.run(blackboard);
+        observer.report(std::any::type_name::<Self>(), status.node_status());
+        status
+    }
 }
 
 /// A behavior that runs until it succeeds.
@@ -168,9 +382,39 @@ pub trait EternalBehavior<B, T> {
     fn run_eternal(&mut self, blackboard: &mut B) -> EternalStatus<T>;
 }
 
+/// Describes a node as a RON value.
+///
+/// Every node is a map with its `type`, such as `"Sequence"`, and a field for each of its
+/// arguments and children: `child` for decorators, `children` for composites, and named
+/// fields such as `condition` for the others. `ConditionRegistry::tree_from_ron` reads
+/// these values back.
 pub trait IntoRon {
     fn into_ron(&self) -> ron::Value;
 }
+
+/// Builds the value produced by `IntoRon` for a node of type `node_type` with the given fields.
+pub(crate) fn node_ron<const N: usize>(
+    node_type: &str,
+    fields: [(&str, ron::Value); N],
+) -> ron::Value {
+    ron::Value::Map(
+        [("type", ron::Value::String(node_type.to_string()))]
+            .into_iter()
+            .chain(fields)
+            .map(|(key, value)| (ron::Value::String(key.to_string()), value))
+            .collect(),
+    )
+}
+
+/// Looks up the field named `key` in a map produced by `node_ron`.
+///
+/// `ron::Map` has no lookup by key, so this searches its entries.
+pub(crate) fn node_field<'a>(map: &'a ron::Map, key: &str) -> Option<&'a ron::Value> {
+    map.iter()
+        .find(|(k, _)| matches!(k, ron::Value::String(k) if k == key))
+        .map(|(_, v)| v)
+}
+
 pub trait CancelSafe {
     fn reset(&mut self);
 }
@@ -201,12 +445,325 @@ impl<T> From<EternalStatus<T>> for Status<T> {
     }
 }
 
+impl<T> From<EternalStatus<T>> for InfallibleStatus<T> {
+    fn from(value: EternalStatus<T>) -> Self {
+        match value {
+            EternalStatus::Running(t) => InfallibleStatus::Running(t),
+        }
+    }
+}
+
+impl<T> From<EternalStatus<T>> for FallibleStatus<T> {
+    fn from(value: EternalStatus<T>) -> Self {
+        match value {
+            EternalStatus::Running(t) => FallibleStatus::Running(t),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use action::{
+        AlwaysFail, AlwaysRunning, AlwaysSucceed, AsyncBehavior, ConditionRegistry, RunOnce,
+        StatefulAction,
+    };
+    use converters::{
+        AssertCancelSafe, CatchPanic, Invert, Rename, ResourceGuard, Subtree, TickLimit,
+    };
     use looping::WhileLoop;
+    use sequence::Race;
 
     use super::*;
 
+    #[test]
+    fn test_run_with_observer() {
+        let mut events = vec![];
+        let mut observer = Observer::new(|event| {
+            events.push((
+                event.name.to_string(),
+                event.path.to_vec(),
+                event.previous,
+                event.status,
+            ))
+        });
+        let mut tree = Rename::new(
+            "check",
+            sequence::Sequence::new((
+                |_: &mut bool| Status::<()>::Success,
+                Invert(|ok: &mut bool| (*ok).into()),
+            )),
+        );
+        let mut ok = true;
+        assert!(tree.run_with_observer(&mut ok, &mut observer).is_err());
+        // Nothing changed, so nothing is reported.
+        assert!(tree.run_with_observer(&mut ok, &mut observer).is_err());
+        ok = false;
+        assert!(tree.run_with_observer(&mut ok, &mut observer).is_ok());
+        drop(observer);
+
+        use NodeStatus::*;
+        assert_eq!(events.len(), 9);
+        assert_eq!(events[0].1, [0, 0]);
+        assert_eq!((events[0].2, events[0].3), (None, Success));
+        assert_eq!(events[1].1, [0, 1, 0]);
+        assert_eq!((events[1].2, events[1].3), (None, Success));
+        assert_eq!(events[2], ("invert".to_string(), vec![0, 1], None, Failure));
+        assert_eq!(events[3], ("sequence".to_string(), vec![0], None, Failure));
+        assert_eq!(events[4], ("check".to_string(), vec![], None, Failure));
+
+        assert_eq!(events[5].1, [0, 1, 0]);
+        assert_eq!((events[5].2, events[5].3), (Some(Success), Failure));
+        assert_eq!(
+            events[6],
+            ("invert".to_string(), vec![0, 1], Some(Failure), Success)
+        );
+        assert_eq!(
+            events[7],
+            ("sequence".to_string(), vec![0], Some(Failure), Success)
+        );
+        assert_eq!(
+            events[8],
+            ("check".to_string(), vec![], Some(Failure), Success)
+        );
+    }
+
+    /// A future that is pending the first time it is polled.
+    struct PendingOnce(bool);
+
+    impl std::future::Future for PendingOnce {
+        type Output = ();
+
+        fn poll(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<()> {
+            if self.0 {
+                std::task::Poll::Ready(())
+            } else {
+                self.0 = true;
+                std::task::Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn test_async_behavior() {
+        let mut behavior = AsyncBehavior::new(|started: &mut usize| {
+            *started += 1;
+            async {
+                PendingOnce(false).await;
+                true
+            }
+        });
+        let mut started = 0;
+        assert!(Behavior::<_, ()>::run(&mut behavior, &mut started).is_running());
+        assert!(Behavior::<_, ()>::run(&mut behavior, &mut started).is_ok());
+        assert_eq!(started, 1);
+
+        assert!(Behavior::<_, ()>::run(&mut behavior, &mut started).is_running());
+        behavior.reset();
+        assert!(Behavior::<_, ()>::run(&mut behavior, &mut started).is_running());
+        assert_eq!(started, 3);
+    }
+
+    #[test]
+    fn test_tick_limit() {
+        let mut limited = TickLimit::new(3, AlwaysRunning);
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_running());
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_running());
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_err());
+
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_err());
+        limited.reset();
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_running());
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_running());
+        assert!(Behavior::<_, ()>::run(&mut limited, &mut ()).is_err());
+    }
+
+    #[test]
+    fn test_tick_limit_counts_finished_ticks() {
+        let mut limited = TickLimit::new(
+            4,
+            AssertCancelSafe(|ticks: &mut usize| {
+                *ticks += 1;
+                if *ticks % 2 == 0 {
+                    Status::Success
+                } else {
+                    Status::Running(())
+                }
+            }),
+        );
+        let mut ticks = 0;
+        assert!(limited.run(&mut ticks).is_running());
+        assert!(limited.run(&mut ticks).is_ok());
+        assert!(limited.run(&mut ticks).is_running());
+        // The child would succeed, but this is the last tick.
+        assert!(limited.run(&mut ticks).is_err());
+        assert!(limited.run(&mut ticks).is_err());
+        assert_eq!(ticks, 4);
+
+        limited.reset();
+        assert!(limited.run(&mut ticks).is_running());
+        assert_eq!(ticks, 5);
+    }
+
+    #[test]
+    fn test_condition_ron_round_trip() {
+        let mut registry = ConditionRegistry::default();
+        registry.register("low_battery", |battery: &usize| *battery < 20);
+
+        let condition = registry.condition("low_battery").unwrap();
+        let mut condition = registry.from_ron(&condition.into_ron()).unwrap();
+        assert_eq!(condition.name, "low_battery");
+        assert!(Behavior::<_, ()>::run(&mut condition, &mut 10).is_ok());
+        assert!(Behavior::<_, ()>::run(&mut condition, &mut 50).is_err());
+
+        let unknown = action::Condition::new("unknown", |_: &usize| true).into_ron();
+        assert!(registry.from_ron(&unknown).is_none());
+        assert!(registry
+            .from_ron(&ron::Value::String("AlwaysSucceed".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    fn test_tree_ron_round_trip() {
+        let mut registry = ConditionRegistry::default();
+        registry.register("even", |n: &usize| n % 2 == 0);
+        registry.register("small", |n: &usize| *n < 10);
+        let condition = |name| registry.condition(name).unwrap();
+
+        let mut tree = Rename::new(
+            "root",
+            sequence::Select::new((
+                sequence::Sequence::new((
+                    TickLimit::new(
+                        3,
+                        sequence::ParallelSequence::new((AlwaysRunning, condition("even"))),
+                    ),
+                    branching::IfElse::new(condition("small"), AlwaysSucceed, AlwaysFail),
+                    branching::TryCatch::new(
+                        AlwaysFail,
+                        sequence::ReactiveSequence::new((condition("even"), AlwaysRunning)),
+                    ),
+                )),
+                sequence::ReactiveSelect::new((
+                    Invert(condition("small")),
+                    WhileLoop::new(condition("even"), AlwaysFail),
+                )),
+                CatchPanic(sequence::Race::new((AlwaysRunning, condition("small")))),
+                sequence::ParallelSelect::new((condition("even"), AlwaysRunning)),
+                sequence::ParallelAny::new((AlwaysFail, AlwaysSucceed)),
+            )),
+        );
+        let ron = tree.into_ron();
+        let mut read: dynamic::DynBehavior<usize, ()> = registry.tree_from_ron(&ron).unwrap();
+        assert_eq!(read.into_ron(), ron);
+
+        for n in [0, 1, 2, 2, 2, 3, 12, 13, 14, 4, 5, 5, 5, 6] {
+            let (mut a, mut b) = (n, n);
+            assert_eq!(
+                Behavior::<_, ()>::run(&mut tree, &mut a).node_status(),
+                read.run(&mut b).node_status()
+            );
+        }
+
+        let shim = converters::InfallibleShim(AlwaysSucceed).into_ron();
+        assert!(registry.tree_from_ron::<()>(&shim).is_none());
+        let empty = sequence::Sequence::new(Vec::<AlwaysFail>::new()).into_ron();
+        assert!(registry.tree_from_ron::<()>(&empty).is_none());
+    }
+
+    #[test]
+    fn test_without_memory() {
+        struct Robot {
+            docked: bool,
+            ticks: usize,
+        }
+        let exits = Rc::new(Cell::new(0));
+        let children = || {
+            let exits = exits.clone();
+            (
+                action::Condition::new("undocked", |robot: &Robot| !robot.docked),
+                StatefulAction::new(
+                    |_: &mut Robot| {},
+                    |robot: &mut Robot| {
+                        robot.ticks += 1;
+                        Status::<()>::Running(())
+                    },
+                    move || exits.set(exits.get() + 1),
+                ),
+            )
+        };
+
+        let mut robot = Robot {
+            docked: false,
+            ticks: 0,
+        };
+        let mut with_memory = sequence::Sequence::new(children());
+        assert!(with_memory.run(&mut robot).is_running());
+        robot.docked = true;
+        assert!(with_memory.run(&mut robot).is_running());
+        assert_eq!(robot.ticks, 2);
+        assert_eq!(exits.get(), 0);
+
+        let mut robot = Robot {
+            docked: false,
+            ticks: 0,
+        };
+        let mut without_memory = sequence::Sequence::new(children()).without_memory();
+        assert!(without_memory.run(&mut robot).is_running());
+        robot.docked = true;
+        assert!(without_memory.run(&mut robot).is_err());
+        assert_eq!(robot.ticks, 1);
+        assert_eq!(exits.get(), 1);
+    }
+
+    #[test]
+    fn test_reactive_typed() {
+        let mut select = sequence::Select::new((
+            AssertCancelSafe(|armed: &mut bool| {
+                if *armed {
+                    FallibleStatus::Running(1)
+                } else {
+                    FallibleStatus::Failure
+                }
+            }),
+            AssertCancelSafe(|_: &mut bool| FallibleStatus::Running(2)),
+        ))
+        .without_memory();
+        let mut armed = false;
+        assert!(matches!(
+            select.run_fallible(&mut armed),
+            FallibleStatus::Running(2)
+        ));
+        armed = true;
+        assert!(matches!(
+            select.run_fallible(&mut armed),
+            FallibleStatus::Running(1)
+        ));
+
+        let mut sequence = sequence::Sequence::new((
+            AssertCancelSafe(|done: &mut bool| {
+                if *done {
+                    InfallibleStatus::Success
+                } else {
+                    InfallibleStatus::Running(1)
+                }
+            }),
+            AssertCancelSafe(|_: &mut bool| InfallibleStatus::<usize>::Success),
+        ))
+        .without_memory();
+        let mut done = false;
+        assert!(matches!(
+            sequence.run_infallible(&mut done),
+            InfallibleStatus::Running(1)
+        ));
+        done = true;
+        assert!(sequence.run_infallible(&mut done).is_ok());
+    }
+
     #[test]
     fn test_sum() {
         let mut sum = 0;
@@ -222,4 +779,99 @@ mod tests {
         assert!(is_ok);
         assert_eq!(sum, 10);
     }
+
+    #[test]
+    fn test_infallible_into_fallible() {
+        let running = InfallibleStatus::Running(2)
+            .map_running(|n| n * 10)
+            .into_fallible();
+        assert!(matches!(running, Some(FallibleStatus::Running(20))));
+        assert!(InfallibleStatus::<()>::Success.into_fallible().is_none());
+        assert!(InfallibleStatus::<()>::Success.into_status().is_ok());
+    }
+
+    #[test]
+    fn test_subtree() {
+        struct Robot {
+            pose: usize,
+            battery: usize,
+        }
+
+        let mut robot = Robot {
+            pose: 0,
+            battery: 100,
+        };
+        let mut drive = Subtree::new(
+            |robot: &mut Robot| &mut robot.pose,
+            WhileLoop::new(
+                |pose: &mut usize| (*pose < 5).into(),
+                |pose: &mut usize| {
+                    *pose += 1;
+                    InfallibleStatus::<()>::Success
+                },
+            ),
+        );
+        assert!(drive.run_infallible(&mut robot).is_ok());
+        assert_eq!(robot.pose, 5);
+        assert_eq!(robot.battery, 100);
+    }
+
+    #[test]
+    fn test_stateful_action_cancel() {
+        let exits = Cell::new(0);
+        let mut action = StatefulAction::new(
+            |ticks: &mut usize| *ticks = 0,
+            |ticks: &mut usize| {
+                *ticks += 1;
+                Status::Running(())
+            },
+            || exits.set(exits.get() + 1),
+        );
+        let mut ticks = 0;
+        assert!(action.run(&mut ticks).is_running());
+        assert!(action.run(&mut ticks).is_running());
+        assert_eq!(ticks, 2);
+        assert_eq!(exits.get(), 0);
+
+        action.reset();
+        action.reset();
+        assert_eq!(exits.get(), 1);
+    }
+
+    #[test]
+    fn test_race_preempts() {
+        let exits = Cell::new(0);
+        let mut race = Race::new((
+            StatefulAction::new(
+                |_: &mut usize| {},
+                |ticks: &mut usize| {
+                    *ticks += 1;
+                    Status::Running(())
+                },
+                || exits.set(exits.get() + 1),
+            ),
+            Invert(RunOnce::from(|| ())),
+        ));
+        let mut ticks = 0;
+        assert!(race.run(&mut ticks).is_running());
+        assert!(race.run(&mut ticks).is_err());
+        assert_eq!(ticks, 2);
+        assert_eq!(exits.get(), 1);
+    }
+
+    #[test]
+    fn test_resource_guard_contention() {
+        let token = Rc::new(Cell::new(false));
+        let mut first = ResourceGuard::new(token.clone(), AlwaysRunning);
+        let mut second = ResourceGuard::new(token.clone(), AlwaysRunning);
+
+        assert!(Behavior::<(), ()>::run(&mut first, &mut ()).is_running());
+        assert!(Behavior::<(), ()>::run(&mut second, &mut ()).is_err());
+        assert!(Behavior::<(), ()>::run(&mut first, &mut ()).is_running());
+
+        first.reset();
+        assert!(!token.get());
+        assert!(Behavior::<(), ()>::run(&mut second, &mut ()).is_running());
+        assert!(Behavior::<(), ()>::run(&mut first, &mut ()).is_err());
+    }
 }
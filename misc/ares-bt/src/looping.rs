@@ -1,6 +1,6 @@
 use crate::{
-    Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior, FallibleStatus,
-    InfallibleBehavior, InfallibleStatus, IntoRon, Status,
+    node_ron, Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior,
+    FallibleStatus, InfallibleBehavior, InfallibleStatus, IntoRon, Observer, Status,
 };
 
 pub struct WhileLoop<A, B> {
@@ -15,16 +15,12 @@ where
     B: IntoRon,
 {
     fn into_ron(&self) -> ron::Value {
-        ron::Value::Map(
+        node_ron(
+            "WhileLoop",
             [
-                (
-                    ron::Value::String("condition".to_string()),
-                    self.condition.into_ron(),
-                ),
-                (ron::Value::String("body".to_string()), self.body.into_ron()),
-            ]
-            .into_iter()
-            .collect(),
+                ("condition", self.condition.into_ron()),
+                ("body", self.body.into_ron()),
+            ],
         )
     }
 }
@@ -35,23 +31,29 @@ where
     B: Behavior<D, T>,
 {
     fn run(&mut self, blackboard: &mut D) -> Status<T> {
-        loop {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut D, observer: &mut Observer) -> Status<T> {
+        let status = loop {
             if self.check_condition {
-                match self.condition.run(blackboard) {
-                    Status::Running(t) => return Status::Running(t),
+                match observer.run_child(0, &mut self.condition, blackboard) {
+                    Status::Running(t) => break Status::Running(t),
                     Status::Success => self.check_condition = false,
-                    Status::Failure => return Status::Success,
+                    Status::Failure => break Status::Success,
                 }
             }
-            match self.body.run(blackboard) {
-                Status::Running(t) => return Status::Running(t),
+            match observer.run_child(1, &mut self.body, blackboard) {
+                Status::Running(t) => break Status::Running(t),
                 Status::Success => self.check_condition = true,
                 Status::Failure => {
                     self.check_condition = true;
-                    return Status::Failure;
+                    break Status::Failure;
                 }
             }
-        }
+        };
+        observer.report("while_loop", status.node_status());
+        status
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::{
-    Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior, FallibleStatus,
-    InfallibleBehavior, InfallibleStatus, IntoRon, Status,
+    node_ron, Behavior, CancelSafe, EternalBehavior, EternalStatus, FallibleBehavior,
+    FallibleStatus, InfallibleBehavior, InfallibleStatus, IntoRon, Observer, Status,
 };
 
 pub struct Sequence<A> {
@@ -15,26 +15,32 @@ macro_rules! impl_seq {
             $($name: Behavior<C1, T>,)+
         {
             fn run(&mut self, blackboard: &mut C1) -> Status<T> {
-                loop {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let status = loop {
                     match self.index {
                         $(
-                            $num => match self.body.$num.run(blackboard) {
-                                Status::Running(t) => return Status::Running(t),
+                            $num => match observer.run_child($num, &mut self.body.$num, blackboard) {
+                                Status::Running(t) => break Status::Running(t),
                                 Status::Success => {
                                     self.index += 1;
                                 }
                                 Status::Failure => {
                                     self.index = 0;
-                                    return Status::Failure;
+                                    break Status::Failure;
                                 }
                             }
                         )+
                         _ => {
                             self.index = 0;
-                            return Status::Success;
+                            break Status::Success;
                         }
                     }
-                }
+                };
+                observer.report("sequence", status.node_status());
+                status
             }
         }
         impl<$($name,)+> CancelSafe for Sequence<($($name,)+)>
@@ -95,16 +101,115 @@ macro_rules! impl_seq {
             $($name: IntoRon,)+
         {
             fn into_ron(&self) -> ron::Value {
-                ron::Value::Map(
-                    [
-                        (ron::Value::String("sequence".to_string()), ron::Value::Seq(
-                            vec![
-                                $(
-                                    self.body.$num.into_ron(),
-                                )+
-                            ].into_iter().collect()
-                        ))
-                    ].into_iter().collect()
+                node_ron(
+                    "Sequence",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
+                )
+            }
+        }
+
+        impl<C1, T, $($name,)+> Behavior<C1, T> for ReactiveSequence<($($name,)+)>
+        where
+            $($name: Behavior<C1, T> + CancelSafe,)+
+        {
+            fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let mut stopped_at = usize::MAX;
+                let status = 'tick: {
+                    $(
+                        match observer.run_child($num, &mut self.body.$num, blackboard) {
+                            Status::Success => {}
+                            status => {
+                                stopped_at = $num;
+                                break 'tick status;
+                            }
+                        }
+                    )+
+                    Status::Success
+                };
+                self.preempt(stopped_at, status.is_running());
+                observer.report("reactive_sequence", status.node_status());
+                status
+            }
+        }
+        impl<C1, T, $($name,)+> InfallibleBehavior<C1, T> for ReactiveSequence<($($name,)+)>
+        where
+            $($name: InfallibleBehavior<C1, T> + CancelSafe,)+
+        {
+            fn run_infallible(&mut self, blackboard: &mut C1) -> InfallibleStatus<T> {
+                let mut stopped_at = usize::MAX;
+                let status = 'tick: {
+                    $(
+                        if let InfallibleStatus::Running(t) = self.body.$num.run_infallible(blackboard) {
+                            stopped_at = $num;
+                            break 'tick InfallibleStatus::Running(t);
+                        }
+                    )+
+                    InfallibleStatus::Success
+                };
+                self.preempt(stopped_at, status.is_running());
+                status
+            }
+        }
+        impl<C1, T, $($name,)+> FallibleBehavior<C1, T> for ReactiveSequence<($($name,)+)>
+        where
+            $($name: FallibleBehavior<C1, T>,)+
+        {
+            fn run_fallible(&mut self, blackboard: &mut C1) -> FallibleStatus<T> {
+                self.running = usize::MAX;
+                self.body.0.run_fallible(blackboard)
+            }
+        }
+        impl<C1, T, $($name,)+> EternalBehavior<C1, T> for ReactiveSequence<($($name,)+)>
+        where
+            $($name: EternalBehavior<C1, T>,)+
+        {
+            fn run_eternal(&mut self, blackboard: &mut C1) -> EternalStatus<T> {
+                self.running = usize::MAX;
+                self.body.0.run_eternal(blackboard)
+            }
+        }
+        impl<$($name,)+> ReactiveSequence<($($name,)+)>
+        where
+            $($name: CancelSafe,)+
+        {
+            /// Records that this tick stopped at the child at `stopped_at`, resetting the
+            /// child that was running on the last tick if it came after.
+            fn preempt(&mut self, stopped_at: usize, running: bool) {
+                // A later child that was running has been preempted by an earlier one.
+                if self.running != usize::MAX && self.running > stopped_at {
+                    match self.running {
+                        $(
+                            $num => self.body.$num.reset(),
+                        )+
+                        _ => unreachable!(),
+                    }
+                }
+                self.running = if running { stopped_at } else { usize::MAX };
+            }
+        }
+        impl<$($name,)+> CancelSafe for ReactiveSequence<($($name,)+)>
+        where
+            $($name: CancelSafe,)+
+        {
+            fn reset(&mut self) {
+                self.running = usize::MAX;
+                $(
+                    self.body.$num.reset();
+                )+
+            }
+        }
+        impl<$($name,)+> IntoRon for ReactiveSequence<($($name,)+)>
+        where
+            $($name: IntoRon,)+
+        {
+            fn into_ron(&self) -> ron::Value {
+                node_ron(
+                    "ReactiveSequence",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
                 )
             }
         }
@@ -118,10 +223,144 @@ impl_seq!(4 A 0 B 1 C 2 D 3);
 impl_seq!(5 A 0 B 1 C 2 D 3 E 4);
 impl_seq!(6 A 0 B 1 C 2 D 3 E 4 F 5);
 
+impl<C1, T, C> Behavior<C1, T> for Sequence<Vec<C>>
+where
+    C: Behavior<C1, T>,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        let status = loop {
+            let Some(child) = self.body.get_mut(self.index) else {
+                self.index = 0;
+                break Status::Success;
+            };
+            match observer.run_child(self.index, child, blackboard) {
+                Status::Running(t) => break Status::Running(t),
+                Status::Success => self.index += 1,
+                Status::Failure => {
+                    self.index = 0;
+                    break Status::Failure;
+                }
+            }
+        };
+        observer.report("sequence", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for Sequence<Vec<C>> {
+    fn reset(&mut self) {
+        self.index = 0;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for Sequence<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "Sequence",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
 impl<A> Sequence<A> {
     pub fn new(body: A) -> Self {
         Self { body, index: 0 }
     }
+
+    /// Converts this into a `ReactiveSequence`, which runs every child from the first on each tick.
+    pub fn without_memory(self) -> ReactiveSequence<A> {
+        ReactiveSequence::new(self.body)
+    }
+}
+
+/// A `Sequence` without memory.
+///
+/// A `Sequence` resumes at the child that was running on the last tick. A `ReactiveSequence`
+/// instead runs every child from the first on each tick, so conditions at the start of the
+/// sequence are checked again while a later child is running. If an earlier child fails or
+/// is now running, the later child that was running is reset.
+pub struct ReactiveSequence<A> {
+    pub body: A,
+    running: usize,
+}
+
+impl<A> ReactiveSequence<A> {
+    pub fn new(body: A) -> Self {
+        Self {
+            body,
+            running: usize::MAX,
+        }
+    }
+}
+
+impl<C1, T, C> Behavior<C1, T> for ReactiveSequence<Vec<C>>
+where
+    C: Behavior<C1, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        let mut stopped_at = usize::MAX;
+        let mut status = Status::Success;
+        for (index, child) in self.body.iter_mut().enumerate() {
+            match observer.run_child(index, child, blackboard) {
+                Status::Success => {}
+                child_status => {
+                    stopped_at = index;
+                    status = child_status;
+                    break;
+                }
+            }
+        }
+        self.preempt(stopped_at, status.is_running());
+        observer.report("reactive_sequence", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for ReactiveSequence<Vec<C>> {
+    fn reset(&mut self) {
+        self.running = usize::MAX;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for ReactiveSequence<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "ReactiveSequence",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
+impl<C: CancelSafe> ReactiveSequence<Vec<C>> {
+    /// Records that this tick stopped at the child at `stopped_at`, resetting the
+    /// child that was running on the last tick if it came after.
+    fn preempt(&mut self, stopped_at: usize, running: bool) {
+        // A later child that was running has been preempted by an earlier one.
+        if self.running != usize::MAX && self.running > stopped_at {
+            self.body[self.running].reset();
+        }
+        self.running = if running { stopped_at } else { usize::MAX };
+    }
 }
 
 pub struct Select<A> {
@@ -136,14 +375,18 @@ macro_rules! impl_sel {
             $($name: Behavior<C1, T>,)+
         {
             fn run(&mut self, blackboard: &mut C1) -> Status<T> {
-                loop {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let status = loop {
                     match self.index {
                         $(
-                            $num => match self.body.$num.run(blackboard) {
-                                Status::Running(t) => return Status::Running(t),
+                            $num => match observer.run_child($num, &mut self.body.$num, blackboard) {
+                                Status::Running(t) => break Status::Running(t),
                                 Status::Success => {
                                     self.index = 0;
-                                    return Status::Success;
+                                    break Status::Success;
                                 }
                                 Status::Failure => {
                                     self.index += 1;
@@ -152,10 +395,12 @@ macro_rules! impl_sel {
                         )+
                         _ => {
                             self.index = 0;
-                            return Status::Failure;
+                            break Status::Failure;
                         }
                     }
-                }
+                };
+                observer.report("select", status.node_status());
+                status
             }
         }
         impl<$($name,)+> CancelSafe for Select<($($name,)+)>
@@ -216,16 +461,115 @@ macro_rules! impl_sel {
             $($name: IntoRon,)+
         {
             fn into_ron(&self) -> ron::Value {
-                ron::Value::Map(
-                    [
-                        (ron::Value::String("select".to_string()), ron::Value::Seq(
-                            vec![
-                                $(
-                                    self.body.$num.into_ron(),
-                                )+
-                            ].into_iter().collect()
-                        ))
-                    ].into_iter().collect()
+                node_ron(
+                    "Select",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
+                )
+            }
+        }
+
+        impl<C1, T, $($name,)+> Behavior<C1, T> for ReactiveSelect<($($name,)+)>
+        where
+            $($name: Behavior<C1, T> + CancelSafe,)+
+        {
+            fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let mut stopped_at = usize::MAX;
+                let status = 'tick: {
+                    $(
+                        match observer.run_child($num, &mut self.body.$num, blackboard) {
+                            Status::Failure => {}
+                            status => {
+                                stopped_at = $num;
+                                break 'tick status;
+                            }
+                        }
+                    )+
+                    Status::Failure
+                };
+                self.preempt(stopped_at, status.is_running());
+                observer.report("reactive_select", status.node_status());
+                status
+            }
+        }
+        impl<C1, T, $($name,)+> InfallibleBehavior<C1, T> for ReactiveSelect<($($name,)+)>
+        where
+            $($name: InfallibleBehavior<C1, T>,)+
+        {
+            fn run_infallible(&mut self, blackboard: &mut C1) -> InfallibleStatus<T> {
+                self.running = usize::MAX;
+                self.body.0.run_infallible(blackboard)
+            }
+        }
+        impl<C1, T, $($name,)+> FallibleBehavior<C1, T> for ReactiveSelect<($($name,)+)>
+        where
+            $($name: FallibleBehavior<C1, T> + CancelSafe,)+
+        {
+            fn run_fallible(&mut self, blackboard: &mut C1) -> FallibleStatus<T> {
+                let mut stopped_at = usize::MAX;
+                let status = 'tick: {
+                    $(
+                        if let FallibleStatus::Running(t) = self.body.$num.run_fallible(blackboard) {
+                            stopped_at = $num;
+                            break 'tick FallibleStatus::Running(t);
+                        }
+                    )+
+                    FallibleStatus::Failure
+                };
+                self.preempt(stopped_at, status.is_running());
+                status
+            }
+        }
+        impl<C1, T, $($name,)+> EternalBehavior<C1, T> for ReactiveSelect<($($name,)+)>
+        where
+            $($name: EternalBehavior<C1, T>,)+
+        {
+            fn run_eternal(&mut self, blackboard: &mut C1) -> EternalStatus<T> {
+                self.running = usize::MAX;
+                self.body.0.run_eternal(blackboard)
+            }
+        }
+        impl<$($name,)+> ReactiveSelect<($($name,)+)>
+        where
+            $($name: CancelSafe,)+
+        {
+            /// Records that this tick stopped at the child at `stopped_at`, resetting the
+            /// child that was running on the last tick if it came after.
+            fn preempt(&mut self, stopped_at: usize, running: bool) {
+                // A later child that was running has been preempted by an earlier one.
+                if self.running != usize::MAX && self.running > stopped_at {
+                    match self.running {
+                        $(
+                            $num => self.body.$num.reset(),
+                        )+
+                        _ => unreachable!(),
+                    }
+                }
+                self.running = if running { stopped_at } else { usize::MAX };
+            }
+        }
+        impl<$($name,)+> CancelSafe for ReactiveSelect<($($name,)+)>
+        where
+            $($name: CancelSafe,)+
+        {
+            fn reset(&mut self) {
+                self.running = usize::MAX;
+                $(
+                    self.body.$num.reset();
+                )+
+            }
+        }
+        impl<$($name,)+> IntoRon for ReactiveSelect<($($name,)+)>
+        where
+            $($name: IntoRon,)+
+        {
+            fn into_ron(&self) -> ron::Value {
+                node_ron(
+                    "ReactiveSelect",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
                 )
             }
         }
@@ -239,10 +583,144 @@ impl_sel!(4 A 0 B 1 C 2 D 3);
 impl_sel!(5 A 0 B 1 C 2 D 3 E 4);
 impl_sel!(6 A 0 B 1 C 2 D 3 E 4 F 5);
 
+impl<C1, T, C> Behavior<C1, T> for Select<Vec<C>>
+where
+    C: Behavior<C1, T>,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        let status = loop {
+            let Some(child) = self.body.get_mut(self.index) else {
+                self.index = 0;
+                break Status::Failure;
+            };
+            match observer.run_child(self.index, child, blackboard) {
+                Status::Running(t) => break Status::Running(t),
+                Status::Success => {
+                    self.index = 0;
+                    break Status::Success;
+                }
+                Status::Failure => self.index += 1,
+            }
+        };
+        observer.report("select", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for Select<Vec<C>> {
+    fn reset(&mut self) {
+        self.index = 0;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for Select<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "Select",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
 impl<A> Select<A> {
     pub fn new(body: A) -> Self {
         Self { body, index: 0 }
     }
+
+    /// Converts this into a `ReactiveSelect`, which runs every child from the first on each tick.
+    pub fn without_memory(self) -> ReactiveSelect<A> {
+        ReactiveSelect::new(self.body)
+    }
+}
+
+/// A `Select` without memory.
+///
+/// A `Select` resumes at the child that was running on the last tick. A `ReactiveSelect`
+/// instead runs every child from the first on each tick, so a higher priority child can
+/// take over from a lower priority one that is running. When that happens, the lower
+/// priority child is reset.
+pub struct ReactiveSelect<A> {
+    pub body: A,
+    running: usize,
+}
+
+impl<A> ReactiveSelect<A> {
+    pub fn new(body: A) -> Self {
+        Self {
+            body,
+            running: usize::MAX,
+        }
+    }
+}
+
+impl<C1, T, C> Behavior<C1, T> for ReactiveSelect<Vec<C>>
+where
+    C: Behavior<C1, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        let mut stopped_at = usize::MAX;
+        let mut status = Status::Failure;
+        for (index, child) in self.body.iter_mut().enumerate() {
+            match observer.run_child(index, child, blackboard) {
+                Status::Failure => {}
+                child_status => {
+                    stopped_at = index;
+                    status = child_status;
+                    break;
+                }
+            }
+        }
+        self.preempt(stopped_at, status.is_running());
+        observer.report("reactive_select", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for ReactiveSelect<Vec<C>> {
+    fn reset(&mut self) {
+        self.running = usize::MAX;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for ReactiveSelect<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "ReactiveSelect",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
+impl<C: CancelSafe> ReactiveSelect<Vec<C>> {
+    /// Records that this tick stopped at the child at `stopped_at`, resetting the
+    /// child that was running on the last tick if it came after.
+    fn preempt(&mut self, stopped_at: usize, running: bool) {
+        // A later child that was running has been preempted by an earlier one.
+        if self.running != usize::MAX && self.running > stopped_at {
+            self.body[self.running].reset();
+        }
+        self.running = if running { stopped_at } else { usize::MAX };
+    }
 }
 
 pub struct ParallelSequence<A> {
@@ -259,13 +737,17 @@ macro_rules! impl_seq {
             Self: CancelSafe
         {
             fn run(&mut self, blackboard: &mut C1) -> Status<T> {
-                loop {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let status = loop {
                     match self.index {
                         $(
-                            $num => match self.body.$num.run(blackboard) {
+                            $num => match observer.run_child($num, &mut self.body.$num, blackboard) {
                                 Status::Running(t) => {
                                     self.index += 1;
-                                    return Status::Running(t);
+                                    break Status::Running(t);
                                 }
                                 Status::Success => {
                                     self.index += 1;
@@ -273,12 +755,12 @@ macro_rules! impl_seq {
                                     if self.succeeded == $len {
                                         self.index = 0;
                                         self.succeeded = 0;
-                                        return Status::Success;
+                                        break Status::Success;
                                     }
                                 }
                                 Status::Failure => {
                                     self.reset();
-                                    return Status::Failure;
+                                    break Status::Failure;
                                 }
                             }
                         )+
@@ -286,7 +768,9 @@ macro_rules! impl_seq {
                             self.index = 0;
                         }
                     }
-                }
+                };
+                observer.report("parallel_sequence", status.node_status());
+                status
             }
         }
         impl<$($name,)+> CancelSafe for ParallelSequence<($($name,)+)>
@@ -387,16 +871,9 @@ macro_rules! impl_seq {
             $($name: IntoRon,)+
         {
             fn into_ron(&self) -> ron::Value {
-                ron::Value::Map(
-                    [
-                        (ron::Value::String("sequence".to_string()), ron::Value::Seq(
-                            vec![
-                                $(
-                                    self.body.$num.into_ron(),
-                                )+
-                            ].into_iter().collect()
-                        ))
-                    ].into_iter().collect()
+                node_ron(
+                    "ParallelSequence",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
                 )
             }
         }
@@ -410,6 +887,70 @@ impl_seq!(4 A 0 B 1 C 2 D 3);
 impl_seq!(5 A 0 B 1 C 2 D 3 E 4);
 impl_seq!(6 A 0 B 1 C 2 D 3 E 4 F 5);
 
+impl<C1, T, C> Behavior<C1, T> for ParallelSequence<Vec<C>>
+where
+    C: Behavior<C1, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        if self.body.is_empty() {
+            return Status::Success;
+        }
+        let status = loop {
+            let Some(child) = self.body.get_mut(self.index) else {
+                self.index = 0;
+                continue;
+            };
+            match observer.run_child(self.index, child, blackboard) {
+                Status::Running(t) => {
+                    self.index += 1;
+                    break Status::Running(t);
+                }
+                Status::Success => {
+                    self.index += 1;
+                    self.succeeded += 1;
+                    if self.succeeded == self.body.len() {
+                        self.index = 0;
+                        self.succeeded = 0;
+                        break Status::Success;
+                    }
+                }
+                Status::Failure => {
+                    self.reset();
+                    break Status::Failure;
+                }
+            }
+        };
+        observer.report("parallel_sequence", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for ParallelSequence<Vec<C>> {
+    fn reset(&mut self) {
+        self.index = 0;
+        self.succeeded = 0;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for ParallelSequence<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "ParallelSequence",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
 impl<A> ParallelSequence<A> {
     pub fn new(body: A) -> Self {
         Self {
@@ -434,17 +975,21 @@ macro_rules! impl_sel {
             Self: CancelSafe
         {
             fn run(&mut self, blackboard: &mut C1) -> Status<T> {
-                loop {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let status = loop {
                     match self.index {
                         $(
-                            $num => match self.body.$num.run(blackboard) {
+                            $num => match observer.run_child($num, &mut self.body.$num, blackboard) {
                                 Status::Running(t) => {
                                     self.index += 1;
-                                    return Status::Running(t);
+                                    break Status::Running(t);
                                 },
                                 Status::Success => {
                                     self.reset();
-                                    return Status::Success;
+                                    break Status::Success;
                                 }
                                 Status::Failure => {
                                     self.index += 1;
@@ -452,7 +997,7 @@ macro_rules! impl_sel {
                                     if self.failed == $len {
                                         self.index = 0;
                                         self.failed = 0;
-                                        return Status::Failure;
+                                        break Status::Failure;
                                     }
                                 }
                             }
@@ -461,7 +1006,9 @@ macro_rules! impl_sel {
                             self.index = 0;
                         }
                     }
-                }
+                };
+                observer.report("parallel_select", status.node_status());
+                status
             }
         }
         impl<$($name,)+> CancelSafe for ParallelSelect<($($name,)+)>
@@ -562,16 +1109,9 @@ macro_rules! impl_sel {
             $($name: IntoRon,)+
         {
             fn into_ron(&self) -> ron::Value {
-                ron::Value::Map(
-                    [
-                        (ron::Value::String("select".to_string()), ron::Value::Seq(
-                            vec![
-                                $(
-                                    self.body.$num.into_ron(),
-                                )+
-                            ].into_iter().collect()
-                        ))
-                    ].into_iter().collect()
+                node_ron(
+                    "ParallelSelect",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
                 )
             }
         }
@@ -585,6 +1125,70 @@ impl_sel!(4 A 0 B 1 C 2 D 3);
 impl_sel!(5 A 0 B 1 C 2 D 3 E 4);
 impl_sel!(6 A 0 B 1 C 2 D 3 E 4 F 5);
 
+impl<C1, T, C> Behavior<C1, T> for ParallelSelect<Vec<C>>
+where
+    C: Behavior<C1, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        if self.body.is_empty() {
+            return Status::Failure;
+        }
+        let status = loop {
+            let Some(child) = self.body.get_mut(self.index) else {
+                self.index = 0;
+                continue;
+            };
+            match observer.run_child(self.index, child, blackboard) {
+                Status::Running(t) => {
+                    self.index += 1;
+                    break Status::Running(t);
+                }
+                Status::Success => {
+                    self.reset();
+                    break Status::Success;
+                }
+                Status::Failure => {
+                    self.index += 1;
+                    self.failed += 1;
+                    if self.failed == self.body.len() {
+                        self.index = 0;
+                        self.failed = 0;
+                        break Status::Failure;
+                    }
+                }
+            }
+        };
+        observer.report("parallel_select", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for ParallelSelect<Vec<C>> {
+    fn reset(&mut self) {
+        self.index = 0;
+        self.failed = 0;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for ParallelSelect<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "ParallelSelect",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
 impl<A> ParallelSelect<A> {
     pub fn new(body: A) -> Self {
         Self {
@@ -608,21 +1212,25 @@ macro_rules! impl_sel {
             Self: CancelSafe
         {
             fn run(&mut self, blackboard: &mut C1) -> Status<T> {
-                loop {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let status = loop {
                     match self.index {
                         $(
-                            $num => match self.body.$num.run(blackboard) {
+                            $num => match observer.run_child($num, &mut self.body.$num, blackboard) {
                                 Status::Running(t) => {
                                     self.index += 1;
-                                    return Status::Running(t);
+                                    break Status::Running(t);
                                 },
                                 Status::Success => {
                                     self.reset();
-                                    return Status::Success;
+                                    break Status::Success;
                                 }
                                 Status::Failure => {
                                     self.reset();
-                                    return Status::Failure;
+                                    break Status::Failure;
                                 }
                             }
                         )+
@@ -630,7 +1238,9 @@ macro_rules! impl_sel {
                             self.index = 0;
                         }
                     }
-                }
+                };
+                observer.report("parallel_any", status.node_status());
+                status
             }
         }
         impl<$($name,)+> CancelSafe for ParallelAny<($($name,)+)>
@@ -726,16 +1336,9 @@ macro_rules! impl_sel {
             $($name: IntoRon,)+
         {
             fn into_ron(&self) -> ron::Value {
-                ron::Value::Map(
-                    [
-                        (ron::Value::String("select".to_string()), ron::Value::Seq(
-                            vec![
-                                $(
-                                    self.body.$num.into_ron(),
-                                )+
-                            ].into_iter().collect()
-                        ))
-                    ].into_iter().collect()
+                node_ron(
+                    "ParallelAny",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
                 )
             }
         }
@@ -746,8 +1349,237 @@ impl_sel!(1 A 0);
 impl_sel!(2 A 0 B 1);
 impl_sel!(3 A 0 B 1 C 2);
 
+impl<C1, T, C> Behavior<C1, T> for ParallelAny<Vec<C>>
+where
+    C: Behavior<C1, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        if self.body.is_empty() {
+            return Status::Failure;
+        }
+        let status = loop {
+            let Some(child) = self.body.get_mut(self.index) else {
+                self.index = 0;
+                continue;
+            };
+            match observer.run_child(self.index, child, blackboard) {
+                Status::Running(t) => {
+                    self.index += 1;
+                    break Status::Running(t);
+                }
+                status => {
+                    self.reset();
+                    break status;
+                }
+            }
+        };
+        observer.report("parallel_any", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for ParallelAny<Vec<C>> {
+    fn reset(&mut self) {
+        self.index = 0;
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for ParallelAny<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "ParallelAny",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
 impl<A> ParallelAny<A> {
     pub fn new(body: A) -> Self {
         Self { body, index: 0 }
     }
 }
+
+/// Runs all of its children on every tick, until any one of them finishes.
+///
+/// The status of the first child to succeed or fail is returned, and every child is
+/// reset. This is useful for racing a behavior against a timeout, for example.
+/// While every child is running, the value of the first child is returned.
+///
+/// Unlike `Select`, which runs its children one after another, every child of a `Race`
+/// is ticked each time the `Race` is ticked, in order, until one of them finishes.
+pub struct Race<A> {
+    pub body: A,
+}
+
+macro_rules! impl_race {
+    ($len: literal $($name: ident $num: tt)+) => {
+        impl<C1, T, $($name,)+> Behavior<C1, T> for Race<($($name,)+)>
+        where
+            $($name: Behavior<C1, T>,)+
+            Self: CancelSafe
+        {
+            fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+                self.run_with_observer(blackboard, &mut Observer::disabled())
+            }
+
+            fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+                let mut running = None;
+                let status = 'tick: {
+                    $(
+                        match observer.run_child($num, &mut self.body.$num, blackboard) {
+                            Status::Running(t) => {
+                                running.get_or_insert(t);
+                            }
+                            status => {
+                                self.reset();
+                                break 'tick status;
+                            }
+                        }
+                    )+
+                    Status::Running(running.unwrap())
+                };
+                observer.report("race", status.node_status());
+                status
+            }
+        }
+        impl<$($name,)+> CancelSafe for Race<($($name,)+)>
+        where
+            $($name: CancelSafe,)+
+        {
+            fn reset(&mut self) {
+                $(
+                    self.body.$num.reset();
+                )+
+            }
+        }
+        impl<C1, T, $($name,)+> InfallibleBehavior<C1, T> for Race<($($name,)+)>
+        where
+            $($name: InfallibleBehavior<C1, T>,)+
+            Self: CancelSafe
+        {
+            fn run_infallible(&mut self, blackboard: &mut C1) -> InfallibleStatus<T> {
+                let mut running = None;
+                $(
+                    match self.body.$num.run_infallible(blackboard) {
+                        InfallibleStatus::Running(t) => {
+                            running.get_or_insert(t);
+                        }
+                        InfallibleStatus::Success => {
+                            self.reset();
+                            return InfallibleStatus::Success;
+                        }
+                    }
+                )+
+                InfallibleStatus::Running(running.unwrap())
+            }
+        }
+        impl<C1, T, $($name,)+> FallibleBehavior<C1, T> for Race<($($name,)+)>
+        where
+            $($name: FallibleBehavior<C1, T>,)+
+            Self: CancelSafe
+        {
+            fn run_fallible(&mut self, blackboard: &mut C1) -> FallibleStatus<T> {
+                let mut running = None;
+                $(
+                    match self.body.$num.run_fallible(blackboard) {
+                        FallibleStatus::Running(t) => {
+                            running.get_or_insert(t);
+                        }
+                        FallibleStatus::Failure => {
+                            self.reset();
+                            return FallibleStatus::Failure;
+                        }
+                    }
+                )+
+                FallibleStatus::Running(running.unwrap())
+            }
+        }
+
+        impl<$($name,)+> IntoRon for Race<($($name,)+)>
+        where
+            $($name: IntoRon,)+
+        {
+            fn into_ron(&self) -> ron::Value {
+                node_ron(
+                    "Race",
+                    [("children", ron::Value::Seq(vec![$(self.body.$num.into_ron(),)+]))],
+                )
+            }
+        }
+    }
+}
+
+impl_race!(1 A 0);
+impl_race!(2 A 0 B 1);
+impl_race!(3 A 0 B 1 C 2);
+impl_race!(4 A 0 B 1 C 2 D 3);
+impl_race!(5 A 0 B 1 C 2 D 3 E 4);
+impl_race!(6 A 0 B 1 C 2 D 3 E 4 F 5);
+
+impl<C1, T, C> Behavior<C1, T> for Race<Vec<C>>
+where
+    C: Behavior<C1, T> + CancelSafe,
+{
+    fn run(&mut self, blackboard: &mut C1) -> Status<T> {
+        self.run_with_observer(blackboard, &mut Observer::disabled())
+    }
+
+    fn run_with_observer(&mut self, blackboard: &mut C1, observer: &mut Observer) -> Status<T> {
+        let mut running = None;
+        let mut status = None;
+        for (index, child) in self.body.iter_mut().enumerate() {
+            match observer.run_child(index, child, blackboard) {
+                Status::Running(t) => {
+                    running.get_or_insert(t);
+                }
+                child_status => {
+                    status = Some(child_status);
+                    break;
+                }
+            }
+        }
+        // With no children, nothing can finish, so there is nothing to wait for either.
+        let status = status.unwrap_or_else(|| running.map_or(Status::Failure, Status::Running));
+        if !status.is_running() {
+            self.reset();
+        }
+        observer.report("race", status.node_status());
+        status
+    }
+}
+
+impl<C: CancelSafe> CancelSafe for Race<Vec<C>> {
+    fn reset(&mut self) {
+        for child in &mut self.body {
+            child.reset();
+        }
+    }
+}
+
+impl<C: IntoRon> IntoRon for Race<Vec<C>> {
+    fn into_ron(&self) -> ron::Value {
+        node_ron(
+            "Race",
+            [(
+                "children",
+                ron::Value::Seq(self.body.iter().map(IntoRon::into_ron).collect()),
+            )],
+        )
+    }
+}
+
+impl<A> Race<A> {
+    pub fn new(body: A) -> Self {
+        Self { body }
+    }
+}
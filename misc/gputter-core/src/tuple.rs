@@ -48,3 +48,96 @@ tuple_idx_impl!(2 C A, B, C, D, E, F);
 tuple_idx_impl!(3 D A, B, C, D, E, F);
 tuple_idx_impl!(4 E A, B, C, D, E, F);
 tuple_idx_impl!(5 F A, B, C, D, E, F);
+
+/// The number of elements in a tuple, known at compile time.
+///
+/// This allows generic code to iterate over `0..T::LEN` together with `StaticIndexable`.
+pub trait StaticLen {
+    const LEN: usize;
+}
+
+macro_rules! tuple_len_impl {
+    ($len: literal $($ty:ident),+) => {
+        impl<$($ty),*> StaticLen for ($($ty,)*) {
+            const LEN: usize = $len;
+        }
+        impl<$($ty: GpuBuffer),*> StaticLen for GpuBufferSet<($($ty,)*)> {
+            const LEN: usize = $len;
+        }
+    }
+}
+
+tuple_len_impl!(1 A);
+tuple_len_impl!(2 A, B);
+tuple_len_impl!(3 A, B, C);
+tuple_len_impl!(4 A, B, C, D);
+tuple_len_impl!(5 A, B, C, D, E);
+tuple_len_impl!(6 A, B, C, D, E, F);
+
+/// A visitor that can be given every element of a tuple by `TupleForEach`.
+///
+/// Implement this for every type that the visitor should accept, which is usually
+/// done with a single blanket implementation over the common trait bound.
+pub trait TupleVisitor<T> {
+    fn visit(&mut self, element: &T);
+}
+
+/// A tuple whose elements can all be visited by `V`, in order.
+pub trait TupleForEach<V> {
+    fn for_each_ref(&self, visitor: &mut V);
+}
+
+macro_rules! tuple_for_each_impl {
+    ($($ty:ident $index: tt),+) => {
+        impl<V, $($ty),*> TupleForEach<V> for ($($ty,)*)
+        where
+            $(V: TupleVisitor<$ty>,)*
+        {
+            fn for_each_ref(&self, visitor: &mut V) {
+                $(visitor.visit(&self.$index);)*
+            }
+        }
+        impl<V, $($ty: GpuBuffer),*> TupleForEach<V> for GpuBufferSet<($($ty,)*)>
+        where
+            $(V: TupleVisitor<$ty>,)*
+        {
+            fn for_each_ref(&self, visitor: &mut V) {
+                $(visitor.visit(&self.buffers.$index);)*
+            }
+        }
+    }
+}
+
+tuple_for_each_impl!(A 0);
+tuple_for_each_impl!(A 0, B 1);
+tuple_for_each_impl!(A 0, B 1, C 2);
+tuple_for_each_impl!(A 0, B 1, C 2, D 3);
+tuple_for_each_impl!(A 0, B 1, C 2, D 3, E 4);
+tuple_for_each_impl!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SizeSum(usize);
+
+    impl<T> TupleVisitor<T> for SizeSum {
+        fn visit(&mut self, _element: &T) {
+            self.0 += size_of::<T>();
+        }
+    }
+
+    #[test]
+    fn test_for_each_size_sum() {
+        let mut sum = SizeSum(0);
+        (1u8, 2u32, [0u16; 3], 4u64).for_each_ref(&mut sum);
+        assert_eq!(sum.0, 1 + 4 + 6 + 8);
+    }
+
+    #[test]
+    fn test_static_len() {
+        assert_eq!(<(u8,)>::LEN, 1);
+        assert_eq!(<(u8, u16, u32)>::LEN, 3);
+        assert_eq!(<(u8, u8, u8, u8, u8, u8)>::LEN, 6);
+    }
+}
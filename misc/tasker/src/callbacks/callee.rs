@@ -1,6 +1,10 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crossbeam::queue::{ArrayQueue, SegQueue};
+use parking_lot::Mutex;
 use tokio::sync::Notify;
 
 use super::caller::try_drop_this_callback;
@@ -49,11 +53,96 @@ impl<T> Queue<T> {
             Self::Unbounded(queue) => queue.pop(),
         }
     }
+
+    /// Returns the number of values in the queue.
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Self::Bounded(queue) => queue.len(),
+            Self::Unbounded(queue) => queue.len(),
+        }
+    }
+
+    /// Returns the maximum number of values in the queue, or `None` if it is unbounded.
+    #[inline]
+    fn capacity(&self) -> Option<usize> {
+        match self {
+            Self::Bounded(queue) => Some(queue.capacity()),
+            Self::Unbounded(_) => None,
+        }
+    }
 }
 
+/// Tracks how long a queue has been nearly full.
+struct DepthWarning {
+    threshold: f32,
+    period: Duration,
+    high_since: Mutex<Option<Instant>>,
+}
+
+impl DepthWarning {
+    /// Records the fill ratio of the queue at `now`, returning `true` if it has
+    /// stayed at or above the threshold for a whole period since the last warning.
+    fn observe(&self, fill: f32, now: Instant) -> bool {
+        let mut high_since = self.high_since.lock();
+        if fill < self.threshold {
+            *high_since = None;
+            return false;
+        }
+        match *high_since {
+            Some(since) if now.duration_since(since) >= self.period => {
+                *high_since = Some(now);
+                true
+            }
+            Some(_) => false,
+            None => {
+                *high_since = Some(now);
+                false
+            }
+        }
+    }
+}
+
+/// The latest value given to a `Subscriber`, and the function that clones it.
+type Latest<T> = (Mutex<Option<T>>, fn(&T) -> T);
+
 struct SubscriberInner<T> {
     queue: Queue<T>,
     notify: Notify,
+    latest: Option<Latest<T>>,
+    depth_warning: Option<DepthWarning>,
+}
+
+impl<T> SubscriberInner<T> {
+    /// Stores a clone of the given value as the latest value, if
+    /// latest value tracking is enabled.
+    #[inline]
+    fn record_latest(&self, value: &T) {
+        if let Some((latest, clone)) = &self.latest {
+            *latest.lock() = Some(clone(value));
+        }
+    }
+
+    /// Logs a warning if the queue has been nearly full for too long, if
+    /// depth warnings are enabled.
+    #[inline]
+    fn check_depth(&self) {
+        let Some(depth_warning) = &self.depth_warning else {
+            return;
+        };
+        let Some(capacity) = self.queue.capacity() else {
+            return;
+        };
+        let len = self.queue.len();
+        if depth_warning.observe(len as f32 / capacity as f32, Instant::now()) {
+            log::warn!(
+                "Subscriber of {} has been at least {:.0}% full for {:?} ({len}/{capacity}). Its consumer may not be keeping up",
+                std::any::type_name::<T>(),
+                depth_warning.threshold * 100.0,
+                depth_warning.period,
+            );
+        }
+    }
 }
 
 pub struct Subscriber<T> {
@@ -67,6 +156,8 @@ impl<T> Subscriber<T> {
             inner: Arc::new(SubscriberInner {
                 queue: Queue::Bounded(ArrayQueue::new(max_size)),
                 notify: Notify::new(),
+                latest: None,
+                depth_warning: None,
             }),
         }
     }
@@ -77,10 +168,80 @@ impl<T> Subscriber<T> {
             inner: Arc::new(SubscriberInner {
                 queue: Queue::Unbounded(SegQueue::new()),
                 notify: Notify::new(),
+                latest: None,
+                depth_warning: None,
             }),
         }
     }
 
+    /// Enables tracking of the latest value given to this `Subscriber`, which
+    /// can then be read with `peek_latest`.
+    ///
+    /// # Panics
+    /// Panics if a callback has already been created from this `Subscriber`.
+    pub fn with_latest(mut self) -> Self
+    where
+        T: Clone,
+    {
+        Arc::get_mut(&mut self.inner)
+            .expect("with_latest must be called before callbacks are created")
+            .latest = Some((Mutex::new(None), T::clone));
+        self
+    }
+
+    /// Enables a warning that is logged when this `Subscriber` stays at least
+    /// `threshold` full (as a fraction of its maximum size) for `period`.
+    ///
+    /// The warning repeats every `period` for as long as the `Subscriber` stays that full,
+    /// which usually means that its consumer cannot keep up and values are being dropped.
+    /// This is disabled by default, and does nothing for unbounded `Subscriber`s.
+    ///
+    /// # Panics
+    /// Panics if a callback has already been created from this `Subscriber`.
+    pub fn with_depth_warning(mut self, threshold: f32, period: Duration) -> Self {
+        Arc::get_mut(&mut self.inner)
+            .expect("with_depth_warning must be called before callbacks are created")
+            .depth_warning = Some(DepthWarning {
+            threshold,
+            period,
+            high_since: Mutex::new(None),
+        });
+        self
+    }
+
+    /// Returns the number of values waiting to be received.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.queue.len()
+    }
+
+    /// Returns `true` if no values are waiting to be received.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of values that can wait to be received, or
+    /// `None` if this `Subscriber` is unbounded.
+    #[inline]
+    pub fn capacity(&self) -> Option<usize> {
+        self.inner.queue.capacity()
+    }
+
+    /// Returns a clone of the latest value given to this `Subscriber` without
+    /// consuming it.
+    ///
+    /// This reflects the newest value even if it was already received from the
+    /// queue, or if it was dropped because the queue was full. Returns `None` if
+    /// no value was given yet, or if latest value tracking was not enabled with
+    /// `with_latest`.
+    pub fn peek_latest(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.inner.latest.as_ref()?.0.lock().clone()
+    }
+
     /// Try to receive a value, returning `None` if no values are available.
     #[inline]
     pub fn try_recv(&self) -> Option<T> {
@@ -124,15 +285,19 @@ impl<T> Subscriber<T> {
     }
 
     pub fn put(&self, value: T) {
+        self.inner.record_latest(&value);
         if self.inner.queue.force_push(value).is_none() {
             self.inner.notify.notify_one();
         }
+        self.inner.check_depth();
     }
 
     pub fn put_conservative(&self, value: T) {
+        self.inner.record_latest(&value);
         if self.inner.queue.push(value).is_ok() {
             self.inner.notify.notify_one();
         }
+        self.inner.check_depth();
     }
 
     /// Creates a callback that will add given values to this `Subscriber`.
@@ -148,9 +313,11 @@ impl<T> Subscriber<T> {
                 try_drop_this_callback();
                 return;
             };
+            inner.record_latest(&value);
             if inner.queue.push(value).is_ok() {
                 inner.notify.notify_one();
             }
+            inner.check_depth();
         }
     }
 
@@ -167,9 +334,117 @@ impl<T> Subscriber<T> {
                 try_drop_this_callback();
                 return;
             };
+            inner.record_latest(&value);
             if inner.queue.force_push(value).is_none() {
                 inner.notify.notify_one();
             }
+            inner.check_depth();
         }
     }
+
+    /// Creates a callback that will add clones of given values to every
+    /// `Subscriber` in `subs`.
+    ///
+    /// If a `Subscriber` is full, its oldest value is dropped. `Subscriber`s
+    /// that have been dropped are skipped, and the callback is only dropped
+    /// once every `Subscriber` has been dropped.
+    pub fn broadcast_callback(subs: &[&Self]) -> impl Fn(T) + Send + Sync
+    where
+        T: Clone + Send,
+    {
+        let inners: Box<[_]> = subs.iter().map(|sub| Arc::downgrade(&sub.inner)).collect();
+        move |value| {
+            let mut any_alive = false;
+            for inner in inners.iter() {
+                let Some(inner) = inner.upgrade() else {
+                    continue;
+                };
+                any_alive = true;
+                inner.record_latest(&value);
+                if inner.queue.force_push(value.clone()).is_none() {
+                    inner.notify.notify_one();
+                }
+                inner.check_depth();
+            }
+            if !any_alive {
+                try_drop_this_callback();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_warning_sustained() {
+        let warning = DepthWarning {
+            threshold: 0.75,
+            period: Duration::from_secs(1),
+            high_since: Mutex::new(None),
+        };
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert!(!warning.observe(1.0, at(0)));
+        assert!(!warning.observe(0.5, at(900)));
+        assert!(!warning.observe(0.8, at(1000)));
+        assert!(!warning.observe(0.8, at(1500)));
+        assert!(warning.observe(1.0, at(2000)));
+        assert!(!warning.observe(1.0, at(2500)));
+        assert!(warning.observe(1.0, at(3000)));
+    }
+
+    #[test]
+    fn test_len_and_capacity() {
+        let sub = Subscriber::new(2).with_depth_warning(0.5, Duration::ZERO);
+        assert_eq!(sub.capacity(), Some(2));
+        sub.put(1);
+        sub.put(2);
+        sub.put(3);
+        assert_eq!(sub.len(), 2);
+        assert_eq!(sub.try_recv(), Some(2));
+        assert_eq!(Subscriber::<()>::new_unbounded().capacity(), None);
+    }
+
+    #[test]
+    fn test_peek_latest() {
+        let sub = Subscriber::new(1).with_latest();
+        assert_eq!(sub.peek_latest(), None);
+
+        let callback = sub.create_conservative_callback();
+        callback(1);
+        callback(2);
+        assert_eq!(sub.peek_latest(), Some(2));
+        assert_eq!(sub.try_recv(), Some(1));
+        assert_eq!(sub.try_recv(), None);
+        assert_eq!(sub.peek_latest(), Some(2));
+
+        let untracked = Subscriber::new(1);
+        untracked.put(1);
+        assert_eq!(untracked.peek_latest(), None);
+    }
+
+    crate::define_callbacks!(TestCallbacks => Fn(value: u32) + Send + Sync);
+
+    #[test]
+    fn test_broadcast_callback() {
+        let sub1 = Subscriber::new_unbounded();
+        let sub2 = Subscriber::new_unbounded();
+        let mut callbacks = TestCallbacks::default();
+        callbacks.add_dyn_fn(Box::new(Subscriber::broadcast_callback(&[&sub1, &sub2])));
+
+        callbacks.call(1);
+        assert_eq!(sub1.try_recv(), Some(1));
+        drop(sub1);
+        callbacks.call(2);
+        assert_eq!(sub2.try_recv(), Some(1));
+        assert_eq!(sub2.try_recv(), Some(2));
+        assert!(!callbacks.is_empty_mut());
+
+        drop(sub2);
+        callbacks.call(3);
+        assert!(callbacks.is_empty_mut());
+    }
 }
@@ -1,7 +1,9 @@
 //! This crate provides a node that can identify apriltags
 //! in images.
 
-use std::{f64::consts::PI, fmt::Debug, num::NonZeroUsize, sync::Arc};
+use std::{
+    collections::hash_map::Entry, f64::consts::PI, fmt::Debug, num::NonZeroUsize, sync::Arc,
+};
 
 use apriltag::{families::Tag16h5, DetectorBuilder, Image, TagParams};
 use apriltag_image::{image::DynamicImage, ImageExt};
@@ -37,6 +39,16 @@ pub struct TagObservation {
     ///
     /// This is a value generated by the apriltag detector.
     pub decision_margin: f32,
+    /// An estimate of the variance of the position in `tag_local_isometry`, in square meters.
+    ///
+    /// This is only estimated when smoothing is enabled, and is infinite otherwise, so that
+    /// consumers do not treat an unknown variance as an exact position. The first
+    /// observation of each tag also has an infinite variance, as there is nothing to compare
+    /// it to.
+    ///
+    /// The estimate is only updated when the tag is detected, so consumers should grow
+    /// the variance with the time since the last observation when detections are intermittent.
+    pub variance: f64,
 }
 
 impl Debug for TagObservation {
@@ -44,6 +56,7 @@ impl Debug for TagObservation {
         f.debug_struct("PoseObservation")
             .field("pose", &self.tag_local_isometry)
             .field("decision_margin", &self.decision_margin)
+            .field("variance", &self.variance)
             .finish()
     }
 }
@@ -68,6 +81,48 @@ struct KnownTag {
     tag_params: TagParams,
 }
 
+struct SmoothedPose {
+    isometry: Isometry3<f64>,
+    variance: f64,
+}
+
+impl SmoothedPose {
+    /// Blends `isometry` into the smoothed pose, returning the new smoothed pose and its variance.
+    fn update(&mut self, isometry: Isometry3<f64>, factor: f64) -> (Isometry3<f64>, f64) {
+        let deviation =
+            (isometry.translation.vector - self.isometry.translation.vector).magnitude_squared();
+        if self.variance.is_infinite() {
+            self.variance = deviation;
+        } else {
+            self.variance = (1.0 - factor) * (self.variance + factor * deviation);
+        }
+        self.isometry = self.isometry.lerp_slerp(&isometry, factor);
+        (self.isometry, self.variance)
+    }
+}
+
+/// Blends `isometry` into the smoothed pose of the tag with the given `id`, returning the
+/// new smoothed pose and its variance.
+///
+/// The first pose of each tag is returned as is, with an infinite variance.
+fn smooth_pose(
+    smoothed_poses: &mut FxHashMap<usize, SmoothedPose>,
+    id: usize,
+    isometry: Isometry3<f64>,
+    factor: f64,
+) -> (Isometry3<f64>, f64) {
+    match smoothed_poses.entry(id) {
+        Entry::Occupied(mut entry) => entry.get_mut().update(isometry, factor),
+        Entry::Vacant(entry) => {
+            entry.insert(SmoothedPose {
+                isometry,
+                variance: f64::INFINITY,
+            });
+            (isometry, f64::INFINITY)
+        }
+    }
+}
+
 /// A Node that can detect apriltags in images.
 ///
 /// Actual detection does not occur until the node
@@ -76,7 +131,15 @@ pub struct AprilTagDetector {
     img_subscriber: Subscriber<Arc<DynamicImage>>,
     detection_callbacks: DetectionCallbacks,
     known_tags: FxHashMap<usize, KnownTag>,
-    pub focal_length_px: f64,
+    smoothing_factor: Option<f64>,
+    /// The horizontal focal length, in pixels.
+    pub fx: f64,
+    /// The vertical focal length, in pixels.
+    pub fy: f64,
+    /// The horizontal position of the principal point, in pixels.
+    pub cx: f64,
+    /// The vertical position of the principal point, in pixels.
+    pub cy: f64,
     pub image_width: u32,
     pub image_height: u32,
 }
@@ -91,7 +154,33 @@ impl AprilTagDetector {
     ///
     /// As such, it is strongly encouraged that the subscription
     /// should not be a sum of multiple subscriptions.
+    ///
+    /// The principal point is assumed to be the center of the image.
+    /// Use `with_intrinsics` for calibrated cameras.
     pub fn new(focal_length_px: f64, image_width: u32, image_height: u32) -> Self {
+        Self::with_intrinsics(
+            focal_length_px,
+            focal_length_px,
+            image_width as f64 / 2.0,
+            image_height as f64 / 2.0,
+            image_width,
+            image_height,
+        )
+    }
+
+    /// Creates a new detector for a camera with the given intrinsics.
+    ///
+    /// `fx` and `fy` are the focal lengths and `cx` and `cy` are the position of
+    /// the principal point, all in pixels. The same requirements on the image size
+    /// as in `new` apply.
+    pub fn with_intrinsics(
+        fx: f64,
+        fy: f64,
+        cx: f64,
+        cy: f64,
+        image_width: u32,
+        image_height: u32,
+    ) -> Self {
         Self {
             img_subscriber: Subscriber::new(
                 std::thread::available_parallelism()
@@ -100,7 +189,11 @@ impl AprilTagDetector {
             ),
             detection_callbacks: DetectionCallbacks::default(),
             known_tags: Default::default(),
-            focal_length_px,
+            smoothing_factor: None,
+            fx,
+            fy,
+            cx,
+            cy,
             image_width,
             image_height,
         }
@@ -124,15 +217,33 @@ impl AprilTagDetector {
                 pose: Isometry3::from_parts(tag_position.into(), tag_orientation),
                 tag_params: TagParams {
                     tagsize: tag_width,
-                    fx: self.focal_length_px,
-                    fy: self.focal_length_px,
-                    cx: self.image_width as f64 / 2.0,
-                    cy: self.image_height as f64 / 2.0,
+                    fx: self.fx,
+                    fy: self.fy,
+                    cx: self.cx,
+                    cy: self.cy,
                 },
             },
         );
     }
 
+    /// Smooths the poses of each tag across consecutive detections with an exponential filter.
+    ///
+    /// `factor` is the weight given to each new detection, and is clamped between 0 and 1.
+    /// Smaller values give smoother poses that lag further behind. Smoothing also enables
+    /// estimation of `TagObservation::variance`.
+    pub fn with_smoothing(mut self, factor: f64) -> Self {
+        self.smoothing_factor = Some(factor.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Disables smoothing, so the raw poses of each detection are emitted.
+    ///
+    /// This is the default.
+    pub fn without_smoothing(mut self) -> Self {
+        self.smoothing_factor = None;
+        self
+    }
+
     pub fn detection_callbacks_ref(&self) -> DetectionCallbacksRef {
         self.detection_callbacks.get_ref()
     }
@@ -144,6 +255,7 @@ impl AprilTagDetector {
             .add_family_bits(Tag16h5::default(), 1)
             .build()
             .unwrap();
+        let mut smoothed_poses: FxHashMap<usize, SmoothedPose> = FxHashMap::default();
 
         loop {
             let Some(img) = self.img_subscriber.recv().block_on() else {
@@ -173,12 +285,80 @@ impl AprilTagDetector {
                     continue;
                 };
 
+                let mut tag_local_isometry = tag_local_isometry.to_na();
+                let mut variance = f64::INFINITY;
+
+                if let Some(factor) = self.smoothing_factor {
+                    (tag_local_isometry, variance) = smooth_pose(
+                        &mut smoothed_poses,
+                        detection.id(),
+                        tag_local_isometry,
+                        factor,
+                    );
+                }
+
                 self.detection_callbacks.call(TagObservation {
-                    tag_local_isometry: tag_local_isometry.to_na(),
+                    tag_local_isometry,
                     decision_margin: detection.decision_margin(),
                     tag_global_isometry: known.pose,
+                    variance,
                 });
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing_constant_pose() {
+        let mut smoothed_poses = FxHashMap::default();
+        let pose = Isometry3::new(Vector3::new(1.0, 2.0, 3.0), Vector3::new(0.1, 0.2, 0.3));
+
+        let (smoothed, variance) = smooth_pose(&mut smoothed_poses, 0, pose, 0.5);
+        assert_eq!(smoothed, pose);
+        assert!(variance.is_infinite());
+
+        for _ in 0..5 {
+            let (smoothed, variance) = smooth_pose(&mut smoothed_poses, 0, pose, 0.5);
+            assert!((smoothed.translation.vector - pose.translation.vector).magnitude() < 1e-9);
+            assert!(smoothed.rotation.angle_to(&pose.rotation) < 1e-9);
+            assert_eq!(variance, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_smoothing_step() {
+        let mut smoothed_poses = FxHashMap::default();
+        let before = Isometry3::translation(0.0, 0.0, 0.0);
+        let after = Isometry3::translation(1.0, 0.0, 0.0);
+        smooth_pose(&mut smoothed_poses, 0, before, 0.5);
+        smooth_pose(&mut smoothed_poses, 0, before, 0.5);
+
+        // Halfway there, with a variance of (1 - 0.5) * (0 + 0.5 * 1^2).
+        let (smoothed, variance) = smooth_pose(&mut smoothed_poses, 0, after, 0.5);
+        assert!((smoothed.translation.x - 0.5).abs() < 1e-9);
+        assert!((variance - 0.25).abs() < 1e-9);
+
+        // (1 - 0.5) * (0.25 + 0.5 * 0.5^2)
+        let (smoothed, variance) = smooth_pose(&mut smoothed_poses, 0, after, 0.5);
+        assert!((smoothed.translation.x - 0.75).abs() < 1e-9);
+        assert!((variance - 0.1875).abs() < 1e-9);
+
+        let mut previous_variance = variance;
+        for _ in 0..30 {
+            let (_, variance) = smooth_pose(&mut smoothed_poses, 0, after, 0.5);
+            assert!(variance < previous_variance);
+            previous_variance = variance;
+        }
+        let (smoothed, variance) = smooth_pose(&mut smoothed_poses, 0, after, 0.5);
+        assert!((smoothed.translation.x - 1.0).abs() < 1e-6);
+        assert!(variance < 1e-6);
+
+        // Other tags are smoothed separately.
+        let (_, variance) = smooth_pose(&mut smoothed_poses, 1, after, 0.5);
+        assert!(variance.is_infinite());
+    }
+}
@@ -0,0 +1,158 @@
+//! A camera that replays images from disk, for running camera pipelines without hardware.
+
+use std::{fmt::Display, path::PathBuf, sync::Arc, time::Duration};
+
+use image::DynamicImage;
+use urobotics_core::{callbacks::caller::CallbacksStorage, time::RateLimiter};
+
+use crate::{ImageCallbacks, ImageCallbacksRef};
+
+/// What an `ImageFileCamera` does after emitting the last image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Playback {
+    /// Stop after emitting every image once.
+    Once,
+    /// Start again from the first image.
+    #[default]
+    Loop,
+}
+
+/// A camera that emits images loaded from files at a fixed rate.
+///
+/// Images are emitted to the same callbacks as a hardware camera, in the order the paths
+/// were given. All images are decoded before the camera starts, so the emitted frames and
+/// their timing do not depend on disk speed.
+pub struct ImageFileCamera {
+    pub paths: Vec<PathBuf>,
+    fps: f64,
+    pub playback: Playback,
+    image_received: ImageCallbacks,
+}
+
+/// The error returned when the frame rate of an `ImageFileCamera` is not positive and finite.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InvalidFps(pub f64);
+
+impl Display for InvalidFps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fps must be positive and finite, got {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidFps {}
+
+impl ImageFileCamera {
+    /// Creates a camera that loops over the images at the given paths, emitting `fps` images a second.
+    ///
+    /// An error is returned if `fps` is not positive and finite.
+    pub fn new(paths: Vec<PathBuf>, fps: f64) -> Result<Self, InvalidFps> {
+        if !(fps.is_finite() && fps > 0.0) {
+            return Err(InvalidFps(fps));
+        }
+        Ok(Self {
+            paths,
+            fps,
+            playback: Playback::default(),
+            image_received: ImageCallbacks::default(),
+        })
+    }
+
+    /// Gets the number of images emitted a second.
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    /// Sets what happens after the last image is emitted.
+    pub fn set_playback(&mut self, playback: Playback) -> &mut Self {
+        self.playback = playback;
+        self
+    }
+
+    /// Gets a reference to the `Signal` that represents received images.
+    pub fn image_received_ref(&self) -> ImageCallbacksRef {
+        self.image_received.get_ref()
+    }
+
+    /// Loads every image, then emits them on a new thread.
+    ///
+    /// The thread exits once every callback has been dropped, so callbacks should be added
+    /// before this is called. An error is returned if any image fails to load, in which case
+    /// no thread is spawned.
+    pub fn spawn(mut self) -> image::ImageResult<()> {
+        let images = self
+            .paths
+            .iter()
+            .map(|path| image::open(path).map(Arc::new))
+            .collect::<image::ImageResult<Vec<Arc<DynamicImage>>>>()?;
+        if images.is_empty() {
+            return Ok(());
+        }
+        let mut rate = RateLimiter::new(Duration::from_secs_f64(self.fps.recip()));
+
+        std::thread::spawn(move || loop {
+            for img in &images {
+                rate.tick_blocking();
+                self.image_received.call(img);
+                if self.image_received.is_empty_mut() {
+                    return;
+                }
+            }
+            if self.playback == Playback::Once {
+                break;
+            }
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use image::{Rgb, RgbImage};
+    use urobotics_core::callbacks::caller::try_drop_this_callback;
+
+    use super::*;
+
+    #[test]
+    fn test_invalid_fps() {
+        assert_eq!(
+            ImageFileCamera::new(vec![], 0.0).err(),
+            Some(InvalidFps(0.0))
+        );
+        assert!(ImageFileCamera::new(vec![], -1.0).is_err());
+        assert!(ImageFileCamera::new(vec![], f64::NAN).is_err());
+        assert!(ImageFileCamera::new(vec![], f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_order_and_loop() {
+        let dir = std::env::temp_dir().join(format!("urobotics-camera-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<_> = [0u8, 1, 2]
+            .into_iter()
+            .map(|i| {
+                let path = dir.join(format!("{i}.png"));
+                RgbImage::from_pixel(1, 1, Rgb([i, 0, 0]))
+                    .save(&path)
+                    .unwrap();
+                path
+            })
+            .collect();
+
+        let camera = ImageFileCamera::new(paths, 1000.0).unwrap();
+        let (tx, rx) = channel();
+        camera.image_received_ref().add_fn(move |img| {
+            if tx.send(img.to_rgb8().get_pixel(0, 0)[0]).is_err() {
+                try_drop_this_callback();
+            }
+        });
+        camera.spawn().unwrap();
+        let received: Vec<u8> = rx.iter().take(7).collect();
+        drop(rx);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(received, [0, 1, 2, 0, 1, 2, 0]);
+    }
+}
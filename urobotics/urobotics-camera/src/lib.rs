@@ -7,7 +7,12 @@
 use std::{
     borrow::Cow,
     path::PathBuf,
-    sync::{Arc, OnceLock},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{sync_channel, SyncSender},
+        Arc, Mutex as StdMutex, OnceLock,
+    },
+    thread::ThreadId,
 };
 
 use image::{DynamicImage, ImageBuffer};
@@ -15,14 +20,16 @@ use nokhwa::{
     pixel_format::RgbFormat,
     query,
     utils::{
-        CameraFormat, CameraIndex, FrameFormat, RequestedFormat, RequestedFormatType, Resolution,
+        CameraFormat, CameraIndex, ControlValueSetter, FrameFormat, KnownCameraControl,
+        RequestedFormat, RequestedFormatType, Resolution,
     },
+    NokhwaError,
 };
 use serde::Deserialize;
 use unfmt::unformat;
 use urobotics_core::{
     define_callbacks, fn_alias,
-    log::error,
+    log::{error, warn},
     service::ServiceExt,
     task::Loggable,
     tokio::sync::{Mutex, OnceCell},
@@ -30,10 +37,28 @@ use urobotics_core::{
 };
 use urobotics_py::{PyRepl, PythonValue, PythonVenvBuilder};
 
+pub mod file;
+
 fn_alias! {
     pub type ImageCallbacksRef = CallbacksRef(&Arc<DynamicImage>) + Send
 }
 define_callbacks!(ImageCallbacks => Fn(image: &Arc<DynamicImage>) + Send);
+fn_alias! {
+    pub type CameraEventCallbacksRef = CallbacksRef(&CameraEvent) + Send
+}
+define_callbacks!(CameraEventCallbacks => Fn(event: &CameraEvent) + Send);
+
+/// A change in the state of a camera connection.
+#[derive(Clone, Debug)]
+pub enum CameraEvent {
+    /// The camera started streaming with the given format.
+    Opened {
+        index: CameraIndex,
+        format: CameraFormat,
+    },
+    /// The camera stopped streaming.
+    Closed { index: CameraIndex },
+}
 
 #[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum CameraIdentifier {
@@ -63,10 +88,25 @@ pub struct CameraConnectionBuilder {
     pub image_height: u32,
     #[serde(default)]
     pub py_venv_builder: PythonVenvBuilder,
+    /// The exposure to lock the camera to when it opens. See `CameraControls::set_exposure`.
+    #[serde(default)]
+    pub exposure: Option<i64>,
+    /// The gain to set when the camera opens. See `CameraControls::set_gain`.
+    #[serde(default)]
+    pub gain: Option<i64>,
+    /// Whether to enable auto exposure when the camera opens. See `CameraControls::set_auto_exposure`.
+    #[serde(default)]
+    pub auto_exposure: Option<bool>,
     #[serde(skip)]
     image_received: ImageCallbacks,
     #[serde(skip)]
     camera_info: Arc<OnceLock<CameraInfo>>,
+    #[serde(skip)]
+    emit_divisor: EmitDivisor,
+    #[serde(skip)]
+    camera_event: CameraEventCallbacks,
+    #[serde(skip)]
+    controls: CameraControls,
 }
 
 pub struct PendingCameraInfo(Arc<OnceLock<CameraInfo>>);
@@ -77,6 +117,250 @@ impl PendingCameraInfo {
     }
 }
 
+/// Controls how many captured frames are emitted by a camera.
+///
+/// A divisor of `n` causes only every `n`th captured frame to be emitted.
+/// The other frames are dropped without being decoded, not averaged. This
+/// can be changed while the camera is running.
+#[derive(Clone, Debug)]
+pub struct EmitDivisor(Arc<AtomicUsize>);
+
+impl Default for EmitDivisor {
+    fn default() -> Self {
+        Self(Arc::new(AtomicUsize::new(1)))
+    }
+}
+
+impl EmitDivisor {
+    /// Sets the divisor. A divisor of 0 is treated as 1.
+    pub fn set(&self, n: usize) {
+        self.0.store(n.max(1), Ordering::Relaxed);
+    }
+
+    /// Gets the divisor.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The V4L2 control that switches between manual and automatic exposure.
+///
+/// `nokhwa` does not have a known control for this, so it can only be set on Linux.
+#[cfg(target_os = "linux")]
+const V4L2_CID_EXPOSURE_AUTO: u128 = 0x009a0901;
+/// `V4L2_EXPOSURE_MANUAL`, a value of `V4L2_CID_EXPOSURE_AUTO`.
+#[cfg(target_os = "linux")]
+const V4L2_EXPOSURE_MANUAL: i64 = 1;
+/// `V4L2_EXPOSURE_APERTURE_PRIORITY`, a value of `V4L2_CID_EXPOSURE_AUTO`.
+///
+/// This is the automatic mode that UVC webcams support. `V4L2_EXPOSURE_AUTO` is usually rejected.
+#[cfg(target_os = "linux")]
+const V4L2_EXPOSURE_APERTURE_PRIORITY: i64 = 3;
+
+enum ControlRequest {
+    Set(KnownCameraControl, ControlValueSetter),
+    Get(KnownCameraControl),
+}
+
+struct PendingControl {
+    request: ControlRequest,
+    /// `None` if the request was made from the camera thread, which cannot wait for a reply.
+    reply: Option<SyncSender<Result<ControlValueSetter, NokhwaError>>>,
+}
+
+#[derive(Default)]
+struct CameraControlsInner {
+    running: AtomicBool,
+    pending: StdMutex<Vec<PendingControl>>,
+    /// The thread that applies requests, while the camera is running.
+    camera_thread: StdMutex<Option<ThreadId>>,
+}
+
+/// A handle to the hardware controls of a running camera.
+///
+/// Controls are passed through to the driver (V4L2 on Linux), so the valid range of each
+/// value depends on the camera. Requests are applied by the camera thread between frames,
+/// so each method blocks for up to one frame period, and returns an error if the camera
+/// is not running or the driver rejects the request.
+///
+/// The camera thread is also the thread that calls the image callbacks, so it cannot wait
+/// for its own requests. When called from an image callback, setters queue the request and
+/// return `Ok` immediately, with the request applied before the next frame and any error
+/// logged instead of returned. Getters return an error from an image callback.
+///
+/// Cheap webcams often do not support every control. Gain is commonly missing, and many
+/// ignore manual exposure unless auto exposure is disabled first. Setting auto exposure
+/// is only supported on Linux.
+#[derive(Clone, Default)]
+pub struct CameraControls(Arc<CameraControlsInner>);
+
+impl CameraControls {
+    fn request(&self, request: ControlRequest) -> Result<ControlValueSetter, NokhwaError> {
+        let on_camera_thread =
+            *self.0.camera_thread.lock().unwrap() == Some(std::thread::current().id());
+        let mut pending = self.0.pending.lock().unwrap();
+        if !self.0.running.load(Ordering::Acquire) {
+            return Err(NokhwaError::GeneralError(
+                "camera is not running".to_string(),
+            ));
+        }
+        if on_camera_thread {
+            return match request {
+                ControlRequest::Set(_, ref value) => {
+                    let value = value.clone();
+                    pending.push(PendingControl {
+                        request,
+                        reply: None,
+                    });
+                    Ok(value)
+                }
+                ControlRequest::Get(_) => Err(NokhwaError::GeneralError(
+                    "camera controls cannot be read from the camera thread".to_string(),
+                )),
+            };
+        }
+        let (reply, reply_rx) = sync_channel(1);
+        pending.push(PendingControl {
+            request,
+            reply: Some(reply),
+        });
+        drop(pending);
+        reply_rx.recv().unwrap_or_else(|_| {
+            Err(NokhwaError::GeneralError(
+                "camera stopped before the control was applied".to_string(),
+            ))
+        })
+    }
+
+    fn get_integer(&self, control: KnownCameraControl) -> Result<i64, NokhwaError> {
+        match self.request(ControlRequest::Get(control))? {
+            ControlValueSetter::Integer(value) => Ok(value),
+            value => Err(NokhwaError::GetPropertyError {
+                property: control.to_string(),
+                error: format!("expected an integer, got {value:?}"),
+            }),
+        }
+    }
+
+    /// Sets the exposure of the camera, in units defined by the driver.
+    ///
+    /// Auto exposure may need to be disabled first with `set_auto_exposure`.
+    pub fn set_exposure(&self, value: i64) -> Result<(), NokhwaError> {
+        self.request(ControlRequest::Set(
+            KnownCameraControl::Exposure,
+            ControlValueSetter::Integer(value),
+        ))
+        .map(drop)
+    }
+
+    /// Gets the current exposure of the camera.
+    pub fn exposure(&self) -> Result<i64, NokhwaError> {
+        self.get_integer(KnownCameraControl::Exposure)
+    }
+
+    /// Sets the gain of the camera, in units defined by the driver.
+    pub fn set_gain(&self, value: i64) -> Result<(), NokhwaError> {
+        self.request(ControlRequest::Set(
+            KnownCameraControl::Gain,
+            ControlValueSetter::Integer(value),
+        ))
+        .map(drop)
+    }
+
+    /// Gets the current gain of the camera.
+    pub fn gain(&self) -> Result<i64, NokhwaError> {
+        self.get_integer(KnownCameraControl::Gain)
+    }
+
+    /// Enables or disables auto exposure.
+    ///
+    /// Disabling auto exposure locks the exposure at its current value until `set_exposure`
+    /// is called. This is only supported on Linux.
+    pub fn set_auto_exposure(&self, enabled: bool) -> Result<(), NokhwaError> {
+        let (control, value) = auto_exposure_control(enabled)?;
+        self.request(ControlRequest::Set(control, value)).map(drop)
+    }
+
+    /// Gets whether auto exposure is enabled. This is only supported on Linux.
+    pub fn auto_exposure(&self) -> Result<bool, NokhwaError> {
+        #[cfg(target_os = "linux")]
+        {
+            self.get_integer(KnownCameraControl::Other(V4L2_CID_EXPOSURE_AUTO))
+                .map(|mode| mode != V4L2_EXPOSURE_MANUAL)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(NokhwaError::NotImplementedError(
+                "auto exposure can only be read on Linux".to_string(),
+            ))
+        }
+    }
+
+    /// Marks the camera as running on the current thread, so that requests are accepted.
+    fn start(&self) {
+        *self.0.camera_thread.lock().unwrap() = Some(std::thread::current().id());
+        self.0.running.store(true, Ordering::Release);
+    }
+
+    /// Marks the camera as stopped and fails every request that was not applied.
+    fn stop(&self) {
+        let mut pending = self.0.pending.lock().unwrap();
+        self.0.running.store(false, Ordering::Release);
+        pending.clear();
+        *self.0.camera_thread.lock().unwrap() = None;
+    }
+
+    /// Applies every pending request to `camera`.
+    fn apply(&self, camera: &mut nokhwa::Camera) {
+        let pending = std::mem::take(&mut *self.0.pending.lock().unwrap());
+        for PendingControl { request, reply } in pending {
+            let result = match request {
+                ControlRequest::Set(control, value) => camera
+                    .set_camera_control(control, value.clone())
+                    .map(|()| value),
+                ControlRequest::Get(control) => camera
+                    .camera_control(control)
+                    .map(|control| control.value()),
+            };
+            match reply {
+                Some(reply) => {
+                    let _ = reply.send(result);
+                }
+                None => {
+                    if let Err(e) = result {
+                        warn!(target: &camera.info().human_name(), "Failed to set camera control: {e}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the control and value that enable or disable auto exposure.
+fn auto_exposure_control(
+    enabled: bool,
+) -> Result<(KnownCameraControl, ControlValueSetter), NokhwaError> {
+    #[cfg(target_os = "linux")]
+    {
+        let mode = if enabled {
+            V4L2_EXPOSURE_APERTURE_PRIORITY
+        } else {
+            V4L2_EXPOSURE_MANUAL
+        };
+        Ok((
+            KnownCameraControl::Other(V4L2_CID_EXPOSURE_AUTO),
+            ControlValueSetter::Integer(mode),
+        ))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = enabled;
+        Err(NokhwaError::NotImplementedError(
+            "auto exposure can only be set on Linux".to_string(),
+        ))
+    }
+}
+
 macro_rules! cam_impl {
     ($self: ident) => {{
         let repl = PY_REPL
@@ -187,18 +471,50 @@ impl CameraConnectionBuilder {
             image_received: ImageCallbacks::default(),
             camera_info: Arc::default(),
             py_venv_builder: PythonVenvBuilder::default(),
+            exposure: None,
+            gain: None,
+            auto_exposure: None,
+            emit_divisor: EmitDivisor::default(),
+            camera_event: CameraEventCallbacks::default(),
+            controls: CameraControls::default(),
         }
     }
 
+    /// Only emit every `n`th captured frame.
+    ///
+    /// Frames are dropped, not averaged. This is useful for consumers that do not need the
+    /// full frame rate, especially for cameras that cannot be set to a lower frame rate.
+    /// Use `emit_divisor` to change this while the camera is running.
+    pub fn set_emit_divisor(&self, n: usize) {
+        self.emit_divisor.set(n);
+    }
+
+    /// Gets a handle to the emit divisor that can change it while the camera is running.
+    pub fn emit_divisor(&self) -> EmitDivisor {
+        self.emit_divisor.clone()
+    }
+
     pub async fn get_camera_info(&mut self) -> PendingCameraInfo {
         PendingCameraInfo(self.camera_info.clone())
     }
 
+    /// Gets a handle to the exposure and gain controls of the camera.
+    ///
+    /// The controls can only be used while the camera is running.
+    pub fn controls(&self) -> CameraControls {
+        self.controls.clone()
+    }
+
     /// Gets a reference to the `Signal` that represents received images.
     pub fn image_received_ref(&self) -> ImageCallbacksRef {
         self.image_received.get_ref()
     }
 
+    /// Gets a reference to the `Signal` that represents the camera opening and closing.
+    pub fn camera_event_ref(&self) -> CameraEventCallbacksRef {
+        self.camera_event.get_ref()
+    }
+
     pub async fn resolve(mut self) -> Result<PendingCameraConnection, nokhwa::NokhwaError> {
         let (camera_index, requested) = cam_impl!(self);
 
@@ -206,6 +522,12 @@ impl CameraConnectionBuilder {
             camera_index,
             requested,
             image_received: self.image_received,
+            emit_divisor: self.emit_divisor,
+            camera_event: self.camera_event,
+            controls: self.controls,
+            exposure: self.exposure,
+            gain: self.gain,
+            auto_exposure: self.auto_exposure,
         })
     }
 }
@@ -214,6 +536,12 @@ pub struct PendingCameraConnection {
     camera_index: CameraIndex,
     requested: RequestedFormat<'static>,
     image_received: ImageCallbacks,
+    emit_divisor: EmitDivisor,
+    camera_event: CameraEventCallbacks,
+    controls: CameraControls,
+    exposure: Option<i64>,
+    gain: Option<i64>,
+    auto_exposure: Option<bool>,
 }
 
 impl PendingCameraConnection {
@@ -232,14 +560,46 @@ impl PendingCameraConnection {
                     }
                 };
             }
-            let mut camera = unwrap!(nokhwa::Camera::new(self.camera_index, self.requested));
+            let mut camera = unwrap!(nokhwa::Camera::new(
+                self.camera_index.clone(),
+                self.requested,
+            ));
             let camera_info = CameraInfo {
                 camera_name: camera.info().human_name(),
             };
             unwrap!(camera.open_stream());
+            self.controls.start();
             let _ = info_tx.send(Ok(camera_info));
+            self.camera_event.call(&CameraEvent::Opened {
+                index: self.camera_index.clone(),
+                format: camera.camera_format(),
+            });
+            let mut frame_count = 0usize;
+
+            // Auto exposure is set first, as some drivers reject a manual exposure while it is on.
+            let initial_controls =
+                self.auto_exposure
+                    .map(auto_exposure_control)
+                    .into_iter()
+                    .chain(self.exposure.map(|exposure| {
+                        Ok((
+                            KnownCameraControl::Exposure,
+                            ControlValueSetter::Integer(exposure),
+                        ))
+                    }))
+                    .chain(self.gain.map(|gain| {
+                        Ok((KnownCameraControl::Gain, ControlValueSetter::Integer(gain)))
+                    }));
+            for result in initial_controls {
+                if let Err(e) =
+                    result.and_then(|(control, value)| camera.set_camera_control(control, value))
+                {
+                    warn!(target: &camera.info().human_name(), "Failed to set camera control: {e}");
+                }
+            }
 
             loop {
+                self.controls.apply(&mut camera);
                 let frame = match camera.frame() {
                     Ok(x) => x,
                     Err(e) => {
@@ -247,6 +607,10 @@ impl PendingCameraConnection {
                         break;
                     }
                 };
+                frame_count += 1;
+                if frame_count % self.emit_divisor.get() != 0 {
+                    continue;
+                }
                 let decoded = frame.decode_image::<RgbFormat>().unwrap();
                 let img = DynamicImage::ImageRgb8(
                     ImageBuffer::from_raw(decoded.width(), decoded.height(), decoded.into_raw())
@@ -255,6 +619,11 @@ impl PendingCameraConnection {
                 let img = Arc::new(img);
                 self.image_received.call(&img);
             }
+
+            self.controls.stop();
+            self.camera_event.call(&CameraEvent::Closed {
+                index: self.camera_index,
+            });
         });
 
         info_rx.recv().unwrap()
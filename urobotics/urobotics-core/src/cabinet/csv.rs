@@ -0,0 +1,386 @@
+//! A minimal CSV writer for `DataDump::new_with_csv_file`.
+//!
+//! Values are flattened into one column per primitive through `serde`, so no CSV crate is needed.
+
+use std::{
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use log::error;
+use serde::ser::{self, Impossible, Serialize};
+
+/// A CSV file that rows are appended to, with a header taken from the first row.
+pub(super) struct CsvFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    /// The columns of the first row, once it has been written.
+    header: Option<Vec<String>>,
+    /// Whether the header should be written before the first row.
+    write_header: bool,
+    flush_interval: Duration,
+    last_flush: Instant,
+}
+
+impl CsvFile {
+    pub(super) fn new(
+        path: PathBuf,
+        append: bool,
+        flush_interval: Duration,
+    ) -> std::io::Result<Self> {
+        let file = if append {
+            OpenOptions::new().create(true).append(true).open(&path)?
+        } else {
+            File::create(&path)?
+        };
+        Ok(Self {
+            write_header: file.metadata()?.len() == 0,
+            writer: BufWriter::new(file),
+            path,
+            header: None,
+            flush_interval,
+            last_flush: Instant::now(),
+        })
+    }
+
+    pub(super) fn write_row(&mut self, data: &impl Serialize) -> std::io::Result<()> {
+        let row = Row::new(data)?;
+        match &self.header {
+            Some(header) => {
+                if *header != row.columns {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Expected the columns {header:?}, got {:?}", row.columns),
+                    ));
+                }
+            }
+            None => {
+                if self.write_header {
+                    write_record(&mut self.writer, &row.columns)?;
+                }
+                self.header = Some(row.columns);
+            }
+        }
+        write_record(&mut self.writer, &row.values)?;
+        if self.last_flush.elapsed() >= self.flush_interval {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CsvFile {
+    fn drop(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            error!("Error flushing {:?}: {e}", self.path);
+        }
+    }
+}
+
+/// Writes `cells` as one line, quoting the cells that contain a delimiter, quote or newline.
+fn write_record(writer: &mut impl Write, cells: &[String]) -> std::io::Result<()> {
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        if cell.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", cell.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(cell.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\n")
+}
+
+/// The columns and values of one row, flattened from a `Serialize` value.
+///
+/// Fields of structs and elements of tuples and sequences become columns named by their path,
+/// such as `pose.x` or `0.secs`. A value that is not a struct, tuple or sequence is put in a
+/// column named `value`.
+#[derive(Default)]
+struct Row {
+    columns: Vec<String>,
+    values: Vec<String>,
+    path: Vec<String>,
+}
+
+impl Row {
+    fn new(data: &impl Serialize) -> Result<Self, Error> {
+        let mut row = Row::default();
+        data.serialize(&mut row)?;
+        Ok(row)
+    }
+
+    fn push(&mut self, value: String) -> Result<(), Error> {
+        self.columns.push(if self.path.is_empty() {
+            "value".to_string()
+        } else {
+            self.path.join(".")
+        });
+        self.values.push(value);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(e: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+macro_rules! serialize_display {
+    ($($method: ident $ty: ty),+) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.push(v.to_string())
+            }
+        )+
+    };
+}
+
+impl<'a> ser::Serializer for &'a mut Row {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Fields<'a>;
+    type SerializeTuple = Fields<'a>;
+    type SerializeTupleStruct = Fields<'a>;
+    type SerializeTupleVariant = Fields<'a>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Fields<'a>;
+    type SerializeStructVariant = Fields<'a>;
+
+    serialize_display!(
+        serialize_bool bool,
+        serialize_i8 i8,
+        serialize_i16 i16,
+        serialize_i32 i32,
+        serialize_i64 i64,
+        serialize_u8 u8,
+        serialize_u16 u16,
+        serialize_u32 u32,
+        serialize_u64 u64,
+        serialize_f32 f32,
+        serialize_f64 f64,
+        serialize_char char,
+        serialize_str &str
+    );
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        Err(Error("Byte arrays cannot be written to CSV".to_string()))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.push(String::new())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.push(String::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.push(String::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.push(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Fields<'a>, Error> {
+        Ok(Fields::new(self))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Fields<'a>, Error> {
+        Ok(Fields::new(self))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Fields<'a>, Error> {
+        Ok(Fields::new(self))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Fields<'a>, Error> {
+        Ok(Fields::new(self))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Impossible<(), Error>, Error> {
+        Err(Error("Maps cannot be written to CSV".to_string()))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Fields<'a>, Error> {
+        Ok(Fields::new(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Fields<'a>, Error> {
+        Ok(Fields::new(self))
+    }
+}
+
+/// Serializes the fields or elements of a value into the columns of a `Row`.
+struct Fields<'a> {
+    row: &'a mut Row,
+    index: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn new(row: &'a mut Row) -> Self {
+        Self { row, index: 0 }
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, name: String, value: &T) -> Result<(), Error> {
+        self.row.path.push(name);
+        let result = value.serialize(&mut *self.row);
+        self.row.path.pop();
+        result
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let index = self.index;
+        self.index += 1;
+        self.field(index.to_string(), value)
+    }
+}
+
+impl ser::SerializeSeq for Fields<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Fields<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Fields<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Fields<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Fields<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Fields<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -11,14 +11,17 @@ use std::{
     io::{BufRead, BufReader, BufWriter, Read, Write},
     path::{Path, PathBuf},
     sync::mpsc::{Receiver, Sender},
+    time::{Duration, Instant},
 };
 
 use bincode::deserialize_from;
-use chrono::{Datelike, Local, Timelike};
+use chrono::{Datelike, Local, NaiveDate, Timelike};
 use fxhash::FxHashSet;
 use log::error;
 use tasker::{attach_drop_guard, callbacks::caller::try_drop_this_callback, detach_drop_guard};
 
+mod csv;
+
 /// A builder for setting up a cabinet.
 pub struct CabinetBuilder {
     /// The path to a folder that the cabinet will use or generate.
@@ -64,17 +67,7 @@ impl CabinetBuilder {
     ) -> &mut Self {
         let mut path = root_path.into();
         path.push(crate_name);
-        let datetime = Local::now();
-        let log_folder_name = format!(
-            "{}-{:0>2}-{:0>2}={:0>2}-{:0>2}-{:0>2}",
-            datetime.year(),
-            datetime.month(),
-            datetime.day(),
-            datetime.hour(),
-            datetime.minute(),
-            datetime.second(),
-        );
-        path.push(log_folder_name);
+        path.push(timestamp());
 
         self.set_cabinet_path(path)
     }
@@ -250,6 +243,148 @@ impl<T, F> DataDump<T, F> {
         let file = File::create(path)?;
         Ok(Self::new_with_text_writer(to_string, BufWriter::new(file)))
     }
+
+    /// Creates a new `DataDump` that writes each `T` as a line into a text file that is replaced according to `rotation`.
+    ///
+    /// Files are created next to `path`, with the timestamp of their creation appended to the file stem.
+    /// Rotation is only checked when data is written, so a file is never created without data to put in it.
+    /// The file is flushed at least once every `flush_interval` while data is arriving, and when the `DataDump` is dropped.
+    pub fn new_with_rotating_text_file(
+        mut to_string: impl FnMut(T) -> String,
+        path: impl Into<PathBuf>,
+        rotation: Rotation,
+        flush_interval: Duration,
+    ) -> std::io::Result<DataDump<T, impl FnMut(T) -> std::io::Result<()>>> {
+        let mut file = RotatingTextFile::new(path.into(), rotation, flush_interval)?;
+        Ok(DataDump::new_with_func(move |data| {
+            file.write_line(&to_string(data))
+        }))
+    }
+
+    /// Creates a new `DataDump` that writes each `T` as a row of a CSV file, assuming that `T` can be serialized with `serde`.
+    ///
+    /// The header is written before the first row. It has a column for each field of `T`, with nested structs and tuples
+    /// flattened into columns named by their path, such as `pose.x` or `0.secs`. If `T` is not a struct or tuple, its only column is `value`.
+    /// Every row must have the same columns as the first, so sequences must always have the same length, and maps and byte arrays are not supported.
+    ///
+    /// If `append` is `true`, rows are added to the end of the file if it already exists, and the header is only written if the file is empty.
+    /// Otherwise, the file is replaced.
+    /// The file is flushed at least once every `flush_interval` while data is arriving, and when the `DataDump` is dropped.
+    pub fn new_with_csv_file(
+        path: impl Into<PathBuf>,
+        append: bool,
+        flush_interval: Duration,
+    ) -> std::io::Result<DataDump<T, impl FnMut(T) -> std::io::Result<()>>>
+    where
+        T: serde::Serialize,
+    {
+        let mut file = csv::CsvFile::new(path.into(), append, flush_interval)?;
+        Ok(DataDump::new_with_func(move |data| file.write_row(&data)))
+    }
+}
+
+/// How often a rotating text file is replaced with a new one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// Only one file is ever written to.
+    None,
+    /// A new file is created when the local date changes.
+    Daily,
+    /// A new file is created once the current file is older than the given duration.
+    EverySecs(Duration),
+}
+
+struct RotatingTextFile {
+    path: PathBuf,
+    rotation: Rotation,
+    flush_interval: Duration,
+    writer: BufWriter<File>,
+    opened_at: Instant,
+    opened_on: NaiveDate,
+    last_flush: Instant,
+}
+
+impl RotatingTextFile {
+    fn new(path: PathBuf, rotation: Rotation, flush_interval: Duration) -> std::io::Result<Self> {
+        let now = Instant::now();
+        Ok(Self {
+            writer: BufWriter::new(File::create(timestamped_path(&path))?),
+            path,
+            rotation,
+            flush_interval,
+            opened_at: now,
+            opened_on: Local::now().date_naive(),
+            last_flush: now,
+        })
+    }
+
+    fn should_rotate(&self) -> bool {
+        match self.rotation {
+            Rotation::None => false,
+            Rotation::Daily => Local::now().date_naive() != self.opened_on,
+            Rotation::EverySecs(duration) => self.opened_at.elapsed() >= duration,
+        }
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.should_rotate() {
+            self.writer.flush()?;
+            self.writer = BufWriter::new(File::create(timestamped_path(&self.path))?);
+            self.opened_at = Instant::now();
+            self.opened_on = Local::now().date_naive();
+        }
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        if self.last_flush.elapsed() >= self.flush_interval {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RotatingTextFile {
+    fn drop(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            error!("Error flushing {:?}: {e}", self.path);
+        }
+    }
+}
+
+/// Formats the current local time for use in file and folder names.
+fn timestamp() -> String {
+    let datetime = Local::now();
+    format!(
+        "{}-{:0>2}-{:0>2}={:0>2}-{:0>2}-{:0>2}",
+        datetime.year(),
+        datetime.month(),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second(),
+    )
+}
+
+/// Appends the current local time to the file stem of `path`.
+///
+/// If that file already exists, a counter is appended as well.
+fn timestamped_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = format!("{stem}-{}", timestamp());
+    let with_stem = |stem: &str| match path.extension() {
+        Some(extension) => path.with_file_name(format!("{stem}.{}", extension.to_string_lossy())),
+        None => path.with_file_name(stem),
+    };
+    let mut new_path = with_stem(&stem);
+    let mut i = 1usize;
+    while new_path.exists() {
+        new_path = with_stem(&format!("{stem}-{i}"));
+        i += 1;
+    }
+    new_path
 }
 
 /// Reads data from a source and calls a callback with the data.
@@ -396,157 +531,288 @@ impl<F: FnMut() -> Option<std::io::Result<()>> + Send + 'static> DataReader<F> {
     }
 }
 
-// Commented out since `Playback`` is unimplemented
-// pub struct Recorder<T, F> {
-//     dump: DataDump<(Duration, T), F>,
-//     instant: Instant,
-// }
-
-// impl<T, F> Recorder<T, F> {
-//     pub fn new_with_dump(dump: DataDump<(Duration, T), F>) -> Self {
-//         Self {
-//             dump,
-//             instant: Instant::now(),
-//         }
-//     }
-
-//     pub fn get_write_callback(&self) -> impl Fn(T) {
-//         let instant = self.instant;
-//         let inner = self.dump.get_write_callback();
-//         move |data| {
-//             inner((instant.elapsed(), data));
-//         }
-//     }
-// }
-
-// impl<T: Send + 'static, F: FnMut((Duration, T)) -> std::io::Result<()> + Send + 'static> SyncTask
-//     for Recorder<T, F>
-// {
-//     type Output = std::io::Result<()>;
-
-//     fn run(self) -> std::io::Result<()> {
-//         self.dump.run()
-//     }
-// }
-
-// pub struct Playback<F> {
-//     reader: DataReader<F>,
-// }
-
-// impl<F> Playback<F> {
-//     pub fn new_with_bincode_reader<T>(
-//         mut reader: impl BufRead,
-//         mut callback: impl FnMut(T),
-//     ) -> Playback<impl FnMut() -> Option<std::io::Result<()>>>
-//     where
-//         T: serde::de::DeserializeOwned,
-//     {
-//         let instant = Instant::now();
-//         let sleeper = SpinSleeper::default();
-//         Playback {
-//             reader: DataReader {
-//                 reader: move || {
-//                     match reader.has_data_left() {
-//                         Ok(true) => {}
-//                         Ok(false) => {
-//                             return None;
-//                         }
-//                         Err(e) => {
-//                             return Some(Err(e));
-//                         }
-//                     }
-//                     match deserialize_from::<_, (Duration, T)>(&mut reader) {
-//                         Ok((duration, data)) => {
-//                             sleeper.sleep(duration - instant.elapsed());
-//                             callback(data);
-//                             Some(Ok(()))
-//                         }
-//                         Err(e) => match *e {
-//                             bincode::ErrorKind::Io(e) => Some(Err(e)),
-//                             _ => Some(Err(std::io::Error::new(std::io::ErrorKind::Other, e))),
-//                         },
-//                     }
-//                 },
-//             },
-//         }
-//     }
-
-//     pub fn new_with_bincode_file<T>(
-//         path: impl AsRef<Path>,
-//         callback: impl FnMut(T),
-//     ) -> std::io::Result<Playback<impl FnMut() -> Option<std::io::Result<()>>>>
-//     where
-//         T: serde::de::DeserializeOwned,
-//     {
-//         let file = File::open(path)?;
-//         Ok(Self::new_with_bincode_reader(
-//             BufReader::new(file),
-//             callback,
-//         ))
-//     }
-
-//     pub fn new_with_text_reader<T>(
-//         delimit: impl Into<String>,
-//         mut from_string: impl FnMut(&str) -> (Duration, T),
-//         mut callback: impl FnMut(T),
-//         mut reader: impl Read,
-//     ) -> Playback<impl FnMut() -> Option<std::io::Result<()>>> {
-//         let delimit = delimit.into();
-//         let mut string_buffer = String::new();
-//         let mut bin_buffer: Vec<u8> = Vec::new();
-//         let mut tmp_buf = [0u8; 4096];
-//         let instant = Instant::now();
-//         let sleeper = SpinSleeper::default();
-//         Playback {
-//             reader: DataReader {
-//                 reader: move || loop {
-//                     let n = match reader.read(&mut tmp_buf) {
-//                         Ok(n) => n,
-//                         Err(e) => return Some(Err(e)),
-//                     };
-//                     bin_buffer.extend_from_slice(&tmp_buf[..n]);
-//                     match std::str::from_utf8(&bin_buffer) {
-//                         Ok(s) => {
-//                             string_buffer.push_str(s);
-//                             bin_buffer.clear();
-//                             if let Some(pos) = string_buffer.find(&delimit) {
-//                                 let (duration, data) = from_string(&string_buffer[..pos]);
-//                                 sleeper.sleep(duration - instant.elapsed());
-//                                 callback(data);
-//                                 string_buffer.drain(..pos + delimit.len());
-//                             }
-//                         }
-//                         Err(_) => {}
-//                     }
-//                     if n == 0 {
-//                         return None;
-//                     }
-//                 },
-//             },
-//         }
-//     }
-
-//     pub fn new_with_text_file<T>(
-//         delimit: impl Into<String>,
-//         from_string: impl FnMut(&str) -> (Duration, T),
-//         callback: impl FnMut(T),
-//         path: impl AsRef<Path>,
-//     ) -> std::io::Result<Playback<impl FnMut() -> Option<std::io::Result<()>>>> {
-//         let file = File::create(path)?;
-//         Ok(Self::new_with_text_reader(
-//             delimit,
-//             from_string,
-//             callback,
-//             file,
-//         ))
-//     }
-// }
-
-// impl<F: FnMut() -> Option<std::io::Result<()>> + Send + 'static> SyncTask for Playback<F> {
-//     type Output = std::io::Result<()>;
-
-//     fn run(self) -> std::io::Result<()> {
-//         // TODO: Implement
-//         self.reader.run()
-//     }
-// }
+/// Records data along with the time it was received, so that it can be replayed by `Playback`.
+///
+/// Each item is stored as a `(Duration, T)` pair, where the `Duration` is the time since the
+/// `Recorder` was created.
+pub struct Recorder<T, F> {
+    dump: DataDump<(Duration, T), F>,
+    instant: Instant,
+}
+
+impl<T, F> Recorder<T, F> {
+    /// Creates a new `Recorder` that writes timestamped data to the given `DataDump`.
+    ///
+    /// Timestamps are measured from when this method is called.
+    pub fn new_with_dump(dump: DataDump<(Duration, T), F>) -> Self {
+        Self {
+            dump,
+            instant: Instant::now(),
+        }
+    }
+
+    /// Creates a new `Recorder` that writes to the given file, assuming that `T` can be serialized with `bincode`.
+    pub fn new_with_bincode_file(
+        path: impl AsRef<Path>,
+    ) -> std::io::Result<Recorder<T, impl FnMut((Duration, T)) -> std::io::Result<()>>>
+    where
+        T: serde::Serialize,
+    {
+        Ok(Recorder::new_with_dump(
+            DataDump::<_, F>::new_with_bincode_file(path)?,
+        ))
+    }
+
+    /// Get a callback that records data.
+    pub fn get_write_callback(&self) -> impl Fn(T) {
+        let instant = self.instant;
+        let inner = self.dump.get_write_callback();
+        move |data| {
+            inner((instant.elapsed(), data));
+        }
+    }
+}
+
+impl<T: Send + 'static, F: FnMut((Duration, T)) -> std::io::Result<()> + Send + 'static>
+    Recorder<T, F>
+{
+    /// Writes recorded data on a new thread.
+    ///
+    /// Data is timestamped when it is given to a write callback, not when it is written. The
+    /// thread writes data in the order it was received, and exits once every write callback
+    /// has been dropped and all of their data has been written. Errors are logged and do not
+    /// stop the thread.
+    pub fn spawn(self) {
+        self.dump.spawn();
+    }
+}
+
+/// How `Playback` paces the data it replays.
+///
+/// With either timing, data is passed to the callback in the order it was recorded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlaybackTiming {
+    /// Data is replayed with the same time between each item as when it was recorded.
+    ///
+    /// Items are scheduled relative to the first item that is replayed, so a slow callback
+    /// delays the item it is called with, but later items are replayed sooner to catch up.
+    #[default]
+    Recorded,
+    /// Data is replayed as fast as it can be read.
+    AsFastAsPossible,
+}
+
+/// Replays data recorded by `Recorder`.
+///
+/// `start_at` seeks into the recording: items recorded before `start_at` are read and
+/// discarded without being passed to the callback, and the first item at or after it is
+/// replayed immediately. With `PlaybackTiming::Recorded`, each later item is replayed at its
+/// recorded time minus the recorded time of that first item, so playback starts without
+/// waiting out the gap between `start_at` and the first item.
+pub struct Playback<F> {
+    reader: DataReader<F>,
+}
+
+impl<F> Playback<F> {
+    /// Creates a new `Playback` with the given reader and callback, assuming that `T` can be deserialized with `bincode`.
+    ///
+    /// Data recorded before `start_at` is skipped without being passed to the callback, and the
+    /// first item after it is replayed immediately. See `Playback` for how replay is timed.
+    pub fn new_with_bincode_reader<T>(
+        mut reader: impl BufRead,
+        mut callback: impl FnMut(T),
+        timing: PlaybackTiming,
+        start_at: Duration,
+    ) -> Playback<impl FnMut() -> Option<std::io::Result<()>>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut first: Option<(Instant, Duration)> = None;
+        Playback {
+            reader: DataReader {
+                reader: move || loop {
+                    match reader.has_data_left() {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            return None;
+                        }
+                        Err(e) => {
+                            return Some(Err(e));
+                        }
+                    }
+                    let (duration, data) = match deserialize_from::<_, (Duration, T)>(&mut reader) {
+                        Ok(x) => x,
+                        Err(e) => {
+                            return match *e {
+                                bincode::ErrorKind::Io(e) => Some(Err(e)),
+                                _ => Some(Err(std::io::Error::new(std::io::ErrorKind::Other, e))),
+                            }
+                        }
+                    };
+                    let Some(offset) = duration.checked_sub(start_at) else {
+                        continue;
+                    };
+                    if timing == PlaybackTiming::Recorded {
+                        let (start, base) = *first.get_or_insert_with(|| (Instant::now(), offset));
+                        if let Some(remaining) =
+                            offset.saturating_sub(base).checked_sub(start.elapsed())
+                        {
+                            std::thread::sleep(remaining);
+                        }
+                    }
+                    callback(data);
+                    return Some(Ok(()));
+                },
+            },
+        }
+    }
+
+    /// Creates a new `Playback` with the given file and callback, assuming that `T` can be deserialized with `bincode`.
+    pub fn new_with_bincode_file<T>(
+        path: impl AsRef<Path>,
+        callback: impl FnMut(T),
+        timing: PlaybackTiming,
+        start_at: Duration,
+    ) -> std::io::Result<Playback<impl FnMut() -> Option<std::io::Result<()>>>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let file = File::open(path)?;
+        Ok(Self::new_with_bincode_reader(
+            BufReader::new(file),
+            callback,
+            timing,
+            start_at,
+        ))
+    }
+}
+
+impl<F: FnMut() -> Option<std::io::Result<()>> + Send + 'static> Playback<F> {
+    /// Replays the data on a new thread, calling the callback from that thread.
+    ///
+    /// The thread exits once the end of the recording is reached, or after logging the first
+    /// error, such as a truncated or corrupt recording.
+    pub fn spawn(self) {
+        self.reader.spawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_creates_second_file() {
+        let dir = std::env::temp_dir().join(format!("urobotics-rotation-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut dump = DataDump::<_, ()>::new_with_rotating_text_file(
+            |n: usize| n.to_string(),
+            dir.join("log.txt"),
+            Rotation::EverySecs(Duration::from_millis(50)),
+            Duration::ZERO,
+        )
+        .unwrap();
+        (dump.writer)(1).unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::thread::sleep(Duration::from_millis(60));
+        (dump.writer)(2).unwrap();
+        drop(dump);
+
+        let mut contents: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        contents.sort();
+        assert_eq!(contents, ["1\n", "2\n"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_csv_file() {
+        #[derive(serde::Serialize)]
+        struct Pose {
+            name: &'static str,
+            position: (f64, f64),
+            tag: Option<u32>,
+        }
+        let path = std::env::temp_dir().join(format!("urobotics-csv-{}.csv", std::process::id()));
+
+        let mut dump = DataDump::<_, ()>::new_with_csv_file(&path, false, Duration::ZERO).unwrap();
+        (dump.writer)(Pose {
+            name: "a, \"b\"",
+            position: (1.0, 2.5),
+            tag: Some(3),
+        })
+        .unwrap();
+        (dump.writer)(Pose {
+            name: "c",
+            position: (0.0, -1.0),
+            tag: None,
+        })
+        .unwrap();
+        drop(dump);
+
+        let mut dump = DataDump::<_, ()>::new_with_csv_file(&path, true, Duration::ZERO).unwrap();
+        (dump.writer)(Pose {
+            name: "d",
+            position: (4.0, 5.0),
+            tag: Some(6),
+        })
+        .unwrap();
+        drop(dump);
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name,position.0,position.1,tag\n\"a, \"\"b\"\"\",1,2.5,3\nc,0,-1,\nd,4,5,6\n"
+        );
+
+        // Rows must keep the columns of the first row.
+        let mut dump = DataDump::<_, ()>::new_with_csv_file(&path, false, Duration::ZERO).unwrap();
+        (dump.writer)(vec![1, 2]).unwrap();
+        assert!((dump.writer)(vec![1, 2, 3]).is_err());
+        drop(dump);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "0,1\n1,2\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_playback_seek_and_timing() {
+        let mut buf = Vec::new();
+        {
+            let mut dump = DataDump::<_, ()>::new_with_bincode_writer(&mut buf);
+            for (millis, n) in [(0, 0usize), (10, 1), (40, 2), (70, 3)] {
+                (dump.writer)((Duration::from_millis(millis), n)).unwrap();
+            }
+        }
+
+        let mut received = vec![];
+        let mut playback = Playback::<()>::new_with_bincode_reader(
+            buf.as_slice(),
+            |n: usize| received.push(n),
+            PlaybackTiming::AsFastAsPossible,
+            Duration::from_millis(10),
+        );
+        while let Some(result) = (playback.reader.reader)() {
+            result.unwrap();
+        }
+        drop(playback);
+        assert_eq!(received, [1, 2, 3]);
+
+        let instant = Instant::now();
+        let mut count = 0usize;
+        let mut playback = Playback::<()>::new_with_bincode_reader(
+            buf.as_slice(),
+            |_: usize| count += 1,
+            PlaybackTiming::Recorded,
+            Duration::from_millis(40),
+        );
+        while let Some(result) = (playback.reader.reader)() {
+            result.unwrap();
+        }
+        drop(playback);
+        assert_eq!(count, 2);
+        assert!(instant.elapsed() >= Duration::from_millis(30));
+    }
+}
@@ -6,7 +6,9 @@
 
 pub mod cabinet;
 pub mod log;
+pub mod pid;
 pub mod service;
+pub mod time;
 
 pub use tasker::*;
 pub use tokio;
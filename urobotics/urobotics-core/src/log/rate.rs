@@ -1,6 +1,10 @@
 //! Simple primitives for tracking the rate at which events occur.
 
-use std::{borrow::Cow, time::Instant};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use log::info;
 
@@ -44,3 +48,92 @@ impl RateLogger {
         self.count += 1;
     }
 }
+
+/// Estimates the frequency of an event over a rolling window of time.
+///
+/// Unlike `RateLogger`, this does not log anything, so the estimate can be compared
+/// against an expected rate or logged however is appropriate.
+pub struct FrequencyCounter {
+    ticks: VecDeque<Instant>,
+    /// The duration of time to estimate the frequency over.
+    pub window: Duration,
+}
+
+impl Default for FrequencyCounter {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1))
+    }
+}
+
+impl FrequencyCounter {
+    /// Creates a new `FrequencyCounter` that estimates frequency over the given `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            ticks: VecDeque::new(),
+            window,
+        }
+    }
+
+    /// Records that the event occurred now.
+    pub fn tick(&mut self) {
+        self.tick_at(Instant::now());
+    }
+
+    /// Records that the event occurred at the given instant.
+    ///
+    /// Instants must be given in chronological order.
+    pub fn tick_at(&mut self, instant: Instant) {
+        self.ticks.push_back(instant);
+        while let Some(&oldest) = self.ticks.front() {
+            if instant.duration_since(oldest) <= self.window {
+                break;
+            }
+            self.ticks.pop_front();
+        }
+    }
+
+    /// Estimates the frequency in Hz of the events within the last `window`.
+    ///
+    /// Returns 0 if fewer than two events occurred within the window, or if they all
+    /// occurred at the same instant.
+    pub fn hz(&self) -> f64 {
+        let now = Instant::now();
+        let mut recent = self
+            .ticks
+            .iter()
+            .filter(|&&tick| now.saturating_duration_since(tick) <= self.window);
+        let Some(&first) = recent.next() else {
+            return 0.0;
+        };
+        let (count, last) = recent.fold((1usize, first), |(count, _), &tick| (count + 1, tick));
+        let span = last.duration_since(first);
+        if count < 2 || span.is_zero() {
+            return 0.0;
+        }
+        (count - 1) as f64 / span.as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_counter() {
+        let mut counter = FrequencyCounter::default();
+        let start = Instant::now() - Duration::from_millis(500);
+        for i in 0..=40 {
+            counter.tick_at(start + Duration::from_millis(10 * i));
+        }
+        assert!((counter.hz() - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_frequency_counter_same_instant() {
+        let mut counter = FrequencyCounter::default();
+        let now = Instant::now();
+        counter.tick_at(now);
+        counter.tick_at(now);
+        assert_eq!(counter.hz(), 0.0);
+    }
+}
@@ -0,0 +1,129 @@
+//! A PID controller for driving an error towards zero.
+
+/// A proportional-integral-derivative controller.
+///
+/// Each call to `step` takes the current error (the setpoint minus the measured value) and
+/// the time since the previous call, and returns the control output. The derivative term is
+/// 0 on the first step after creation or `reset`, so a large initial error does not cause a
+/// spike in the output.
+///
+/// Two limits prevent integral windup. `integral_limit` bounds the magnitude of the
+/// accumulated error, and while the output is clamped to `output_limit`, error that would
+/// push the output further past the limit is not accumulated.
+#[derive(Clone, Copy, Debug)]
+pub struct Pid {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+    /// The largest magnitude of the output, if any.
+    pub output_limit: Option<f64>,
+    /// The largest magnitude of the accumulated error, if any.
+    ///
+    /// This limits the integral itself, so the most the integral term can contribute to
+    /// the output is `ki * integral_limit`.
+    pub integral_limit: Option<f64>,
+    integral: f64,
+    last_error: Option<f64>,
+}
+
+impl Pid {
+    /// Creates a new `Pid` with the given gains and no limits.
+    pub fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            output_limit: None,
+            integral_limit: None,
+            integral: 0.0,
+            last_error: None,
+        }
+    }
+
+    /// Clamps the output to between `-limit` and `limit`.
+    pub fn with_output_limit(mut self, limit: f64) -> Self {
+        self.output_limit = Some(limit);
+        self
+    }
+
+    /// Clamps the accumulated error to between `-limit` and `limit`.
+    pub fn with_integral_limit(mut self, limit: f64) -> Self {
+        self.integral_limit = Some(limit);
+        self
+    }
+
+    /// Computes the output for the given `error`, `dt` seconds after the previous step.
+    pub fn step(&mut self, error: f64, dt: f64) -> f64 {
+        let previous_integral = self.integral;
+        self.integral += error * dt;
+        if let Some(limit) = self.integral_limit {
+            self.integral = self.integral.clamp(-limit, limit);
+        }
+        let derivative = match self.last_error {
+            Some(last_error) if dt > 0.0 => (error - last_error) / dt,
+            _ => 0.0,
+        };
+        self.last_error = Some(error);
+
+        let output = self.kp * error + self.ki * self.integral + self.kd * derivative;
+        let Some(limit) = self.output_limit else {
+            return output;
+        };
+        let clamped = output.clamp(-limit, limit);
+        if clamped != output && output.signum() == error.signum() {
+            self.integral = previous_integral;
+        }
+        clamped
+    }
+
+    /// Gets the accumulated error.
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Clears the accumulated error and the previous error.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drives a plant whose velocity is the controller output towards `setpoint`.
+    fn simulate(pid: &mut Pid, setpoint: f64, steps: usize) -> f64 {
+        let dt = 0.01;
+        let mut position = 0.0;
+        for _ in 0..steps {
+            position += pid.step(setpoint - position, dt) * dt;
+        }
+        position
+    }
+
+    #[test]
+    fn test_step_response() {
+        let mut pid = Pid::new(2.0, 0.5, 0.1).with_output_limit(1.0);
+        let position = simulate(&mut pid, 1.0, 2000);
+        assert!((position - 1.0).abs() < 1e-3, "{position}");
+    }
+
+    #[test]
+    fn test_anti_windup() {
+        let mut pid = Pid::new(1.0, 1.0, 0.0).with_integral_limit(0.5);
+        for _ in 0..100 {
+            pid.step(10.0, 0.1);
+        }
+        assert_eq!(pid.integral(), 0.5);
+
+        let mut pid = Pid::new(1.0, 1.0, 0.0).with_output_limit(1.0);
+        for _ in 0..100 {
+            assert_eq!(pid.step(10.0, 0.1), 1.0);
+        }
+        assert_eq!(pid.integral(), 0.0);
+
+        pid.reset();
+        assert_eq!(pid.step(0.5, 0.1), 0.55);
+    }
+}
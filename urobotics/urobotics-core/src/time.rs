@@ -0,0 +1,294 @@
+//! Utilities for running code on a schedule.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use tasker::callbacks::caller::try_drop_this_callback;
+use tokio::sync::Notify;
+
+/// A source of the current time, and a way to wait for time to pass.
+///
+/// Code that reads the time through a `TimeSource` instead of `Instant::now` can be
+/// tested deterministically with a `FakeClock`.
+pub trait TimeSource {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+    /// Waits until `duration` has passed.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send;
+    /// Blocks the current thread until `duration` has passed.
+    fn sleep_blocking(&self, duration: Duration);
+}
+
+/// The system clock, using `Instant::now` and `tokio` to sleep.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealClock;
+
+impl TimeSource for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+
+    fn sleep_blocking(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// A clock that only moves forward when told to.
+///
+/// Sleeping on a `FakeClock` advances it by the sleep duration and returns immediately.
+/// Clones share the same time.
+#[derive(Clone, Debug)]
+pub struct FakeClock {
+    start: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed_nanos: Arc::default(),
+        }
+    }
+}
+
+impl FakeClock {
+    /// Creates a new `FakeClock` starting at the current time.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns how far the clock has moved since it was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::Relaxed))
+    }
+}
+
+impl TimeSource for FakeClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        self.advance(duration);
+        std::future::ready(())
+    }
+
+    fn sleep_blocking(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+/// Holds a loop to a fixed rate.
+///
+/// Each call to `tick` sleeps just long enough for the time between
+/// consecutive ticks to equal the target period. If a cycle takes longer
+/// than the period, the overrun is counted and the next cycle starts
+/// immediately, instead of trying to catch up with a burst of ticks.
+pub struct RateLimiter<C = RealClock> {
+    period: Duration,
+    next: Option<Instant>,
+    overruns: usize,
+    clock: C,
+}
+
+impl RateLimiter {
+    /// Creates a new `RateLimiter` that ticks once every `period`.
+    pub fn new(period: Duration) -> Self {
+        Self::with_time_source(period, RealClock)
+    }
+
+    /// Creates a new `RateLimiter` that ticks `hz` times a second.
+    pub fn from_hz(hz: f64) -> Self {
+        Self::new(Duration::from_secs_f64(hz.recip()))
+    }
+}
+
+impl<C: TimeSource> RateLimiter<C> {
+    /// Creates a new `RateLimiter` that ticks once every `period`, as measured by `clock`.
+    pub fn with_time_source(period: Duration, clock: C) -> Self {
+        Self {
+            period,
+            next: None,
+            overruns: 0,
+            clock,
+        }
+    }
+
+    /// Gets the target period between ticks.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the number of cycles that took longer than the period.
+    pub fn overrun_count(&self) -> usize {
+        self.overruns
+    }
+
+    /// Returns how long to sleep for, and advances to the next cycle.
+    fn advance(&mut self) -> Duration {
+        let now = self.clock.now();
+        let Some(next) = self.next else {
+            self.next = Some(now + self.period);
+            return Duration::ZERO;
+        };
+        let remaining = next.saturating_duration_since(now);
+        if remaining.is_zero() {
+            self.overruns += 1;
+            self.next = Some(now + self.period);
+        } else {
+            self.next = Some(next + self.period);
+        }
+        remaining
+    }
+
+    /// Sleeps until the next cycle should begin.
+    ///
+    /// The first call returns immediately.
+    pub async fn tick(&mut self) {
+        let remaining = self.advance();
+        if !remaining.is_zero() {
+            self.clock.sleep(remaining).await;
+        }
+    }
+
+    /// Blocks the current thread until the next cycle should begin.
+    ///
+    /// The first call returns immediately.
+    pub fn tick_blocking(&mut self) {
+        let remaining = self.advance();
+        if !remaining.is_zero() {
+            self.clock.sleep_blocking(remaining);
+        }
+    }
+}
+
+/// Detects when a stream of messages goes silent.
+///
+/// Every message that should keep the `Watchdog` alive must be passed to a callback
+/// made by `create_callback`, or `feed` must be called. If no message arrives within
+/// the timeout, the function given to `run` is invoked once. It will not be invoked
+/// again until messages resume and then stop again.
+pub struct Watchdog {
+    timeout: Duration,
+    fed: Arc<Notify>,
+}
+
+impl Watchdog {
+    /// Creates a new `Watchdog` that fires after `timeout` of silence.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            fed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Resets the timeout as if a message was received.
+    pub fn feed(&self) {
+        self.fed.notify_one();
+    }
+
+    /// Creates a callback that resets the timeout whenever it is called.
+    ///
+    /// The given values are dropped immediately.
+    pub fn create_callback<T>(&self) -> impl Fn(T) + Send + Sync {
+        let fed = Arc::downgrade(&self.fed);
+        move |_| {
+            let Some(fed) = fed.upgrade() else {
+                try_drop_this_callback();
+                return;
+            };
+            fed.notify_one();
+        }
+    }
+
+    /// Watches for silence forever, calling `on_silent` whenever the stream goes silent.
+    pub async fn run(self, mut on_silent: impl FnMut()) -> ! {
+        let mut fired = false;
+        loop {
+            match tokio::time::timeout(self.timeout, self.fed.notified()).await {
+                Ok(()) => fired = false,
+                Err(_) => {
+                    if !fired {
+                        fired = true;
+                        on_silent();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_fake_clock() {
+        let clock = FakeClock::new();
+        let period = Duration::from_millis(100);
+        let mut limiter = RateLimiter::with_time_source(period, clock.clone());
+
+        limiter.tick_blocking();
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+
+        clock.advance(Duration::from_millis(30));
+        limiter.tick_blocking();
+        assert_eq!(clock.elapsed(), period);
+        assert_eq!(limiter.overrun_count(), 0);
+
+        clock.advance(Duration::from_millis(250));
+        limiter.tick_blocking();
+        assert_eq!(clock.elapsed(), Duration::from_millis(350));
+        assert_eq!(limiter.overrun_count(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watchdog() {
+        use std::sync::atomic::AtomicUsize;
+
+        let watchdog = Watchdog::new(Duration::from_millis(100));
+        let feed = watchdog.create_callback();
+        let fired = Arc::new(AtomicUsize::new(0));
+        let task = tokio::spawn(watchdog.run({
+            let fired = fired.clone();
+            move || {
+                fired.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+
+        for _ in 0..5 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            feed(());
+        }
+        assert_eq!(fired.load(Ordering::Relaxed), 0);
+
+        // Fires once at 100 ms of silence, and not again while it stays silent.
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+
+        feed(());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(fired.load(Ordering::Relaxed), 2);
+
+        task.abort();
+    }
+}
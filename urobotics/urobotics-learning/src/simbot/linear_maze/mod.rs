@@ -1,26 +1,29 @@
 use std::{
-    f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4},
+    f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, PI},
+    fmt::Display,
     io::Write,
+    ops::RangeInclusive,
     sync::atomic::Ordering,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use fxhash::FxBuildHasher;
 use indexmap::IndexSet;
 use nalgebra::{Rotation2, Vector2};
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use spin_sleep::SpinSleeper;
 use urobotics::{
     define_callbacks, fn_alias,
-    log::OwoColorize,
+    log::{error, info, OwoColorize},
     parking_lot::{RwLockReadGuard, RwLockWriteGuard},
-    task::SyncTask,
+    task::{Loggable, SyncTask},
 };
 
 use crate::simbot::END_POINT;
 
 use super::{
-    Obstacles, COLLIDED, DRIVE_HISTORY, OBSTACLES, REFRESH_RATE, SIMBOT_DIRECTION, SIMBOT_ORIGIN,
+    Obstacles, COLLIDED, COLLISION_RADIUS, DRIVE_HISTORY, OBSTACLES, REFRESH_RATE,
+    SIMBOT_DIRECTION, SIMBOT_ORIGIN,
 };
 
 pub mod solution;
@@ -29,16 +32,145 @@ define_callbacks!(RaycastCallbacks => Fn(metric: (Vector2<f64>, f64)) + Send);
 fn_alias! {
     pub type RaycastCallbacksRef = CallbacksRef((Vector2<f64>, f64)) + Send
 }
+define_callbacks!(LidarCallbacks => Fn(hits: &[(Vector2<f64>, f64)]) + Send);
+fn_alias! {
+    pub type LidarCallbacksRef = CallbacksRef(&[(Vector2<f64>, f64)]) + Send
+}
 
-#[derive(Default)]
 pub struct LinearMazeSensor {
     raycast_callbacks: RaycastCallbacks,
+    lidar_callbacks: LidarCallbacks,
+    /// The seed used to generate the maze.
+    ///
+    /// If set, the generated maze (and thus `maze.toml` and `maze.obj`) will be
+    /// identical across runs. Otherwise, a random maze is generated each run.
+    /// Do note that whether or not a program succeeds still depends on wall clock
+    /// time, so results may still differ across runs.
+    pub seed: Option<u64>,
+    /// The maximum amount of time a program has to reach the end.
+    ///
+    /// Defaults to 5 seconds.
+    pub time_limit: Duration,
+    /// The range of the number of turning segments in the maze.
+    ///
+    /// Defaults to `7..=13`.
+    pub segment_range: RangeInclusive<usize>,
+    /// The number of raycasts made for each lidar scan.
+    ///
+    /// Defaults to 9.
+    pub lidar_ray_count: usize,
+    /// The angle in radians that the raycasts of each lidar scan are spread
+    /// over, centered on the direction of the simbot.
+    ///
+    /// Defaults to PI.
+    pub lidar_fov: f64,
+    /// The radius of the simbot in meters.
+    ///
+    /// The simbot collides when it drives to within this distance of an obstacle,
+    /// measured along its direction of travel. Stopping exactly at this distance is not
+    /// a collision. Defaults to 0.0, which treats the simbot as a point.
+    pub collision_radius: f64,
+    /// The maximum distance in meters between the simbot and the end point for
+    /// the simbot to have reached the end.
+    ///
+    /// Defaults to 0.5.
+    pub goal_tolerance: f64,
+}
+
+impl Default for LinearMazeSensor {
+    fn default() -> Self {
+        Self {
+            raycast_callbacks: RaycastCallbacks::default(),
+            lidar_callbacks: LidarCallbacks::default(),
+            seed: None,
+            time_limit: Duration::from_secs(5),
+            segment_range: 7..=13,
+            lidar_ray_count: 9,
+            lidar_fov: PI,
+            collision_radius: 0.0,
+            goal_tolerance: 0.5,
+        }
+    }
 }
 
 impl LinearMazeSensor {
     pub fn raycast_callbacks_ref(&self) -> RaycastCallbacksRef {
         self.raycast_callbacks.get_ref()
     }
+
+    /// Gets a reference to the callbacks that receive lidar scans.
+    ///
+    /// Each scan contains the hit point and distance of every raycast that
+    /// hit an obstacle, ordered from right to left. Raycasts that did not hit
+    /// anything are omitted.
+    pub fn lidar_callbacks_ref(&self) -> LidarCallbacksRef {
+        self.lidar_callbacks.get_ref()
+    }
+}
+
+/// Performs `ray_count` raycasts evenly spread over `fov` radians, centered on `direction`.
+fn lidar_scan(
+    obstacles: &Obstacles,
+    origin: Vector2<f64>,
+    direction: f64,
+    ray_count: usize,
+    fov: f64,
+) -> Vec<(Vector2<f64>, f64)> {
+    (0..ray_count)
+        .filter_map(|i| {
+            let angle = if ray_count == 1 {
+                direction
+            } else {
+                direction - fov / 2.0 + fov * i as f64 / (ray_count - 1) as f64
+            };
+            obstacles.raycast(origin, angle)
+        })
+        .collect()
+}
+
+/// The outcome of a run through the linear maze.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MazeOutcome {
+    /// Whether or not the simbot reached the end.
+    pub reached: bool,
+    /// Whether or not the simbot collided with an obstacle.
+    pub collided: bool,
+    /// The time taken to reach the end, if it was reached.
+    pub elapsed: Option<Duration>,
+    /// The number of times the simbot drove.
+    pub path_len: usize,
+    /// The time limit the simbot had to reach the end.
+    pub time_limit: Duration,
+}
+
+impl Display for MazeOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.collided {
+            write!(f, "Your program collided with an obstacle!")
+        } else if let Some(elapsed) = self.elapsed.filter(|_| self.reached) {
+            let msg = format!(
+                "Your program reached the end in {:.2} secs!",
+                elapsed.as_secs_f32()
+            );
+            write!(f, "{}", msg.green())
+        } else {
+            write!(
+                f,
+                "Your program took longer than {:.2} secs to reach the end",
+                self.time_limit.as_secs_f32()
+            )
+        }
+    }
+}
+
+impl Loggable for MazeOutcome {
+    fn log(&self) {
+        if self.reached {
+            info!("{self}");
+        } else {
+            error!("{self}");
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -47,123 +179,25 @@ enum TurnType {
     Right,
 }
 
-pub(super) fn generate_linear_maze() -> (RwLockReadGuard<'static, Obstacles>, Vector2<f64>) {
-    let mut rng = thread_rng();
+pub(super) fn generate_linear_maze(
+    seed: Option<u64>,
+    segment_range: RangeInclusive<usize>,
+) -> (RwLockReadGuard<'static, Obstacles>, Vector2<f64>) {
     let mut obstacles = OBSTACLES.write();
     let end_point;
 
     if obstacles.vertices.is_empty() {
-        let mut origin = SIMBOT_ORIGIN.load();
-        let mut direction = SIMBOT_DIRECTION.load();
-        let mut vertices = IndexSet::<Vector2<isize>, FxBuildHasher>::default();
-
-        macro_rules! add_wall {
-            ($from:expr, $to:expr) => {
-                let from = $from / 0.5;
-                let to = $to / 0.5;
-                let from = Vector2::new(from.x.round() as isize, from.y.round() as isize);
-                let to = Vector2::new(to.x.round() as isize, to.y.round() as isize);
-
-                let (from_index, from_is_new) = vertices.insert_full(from);
-                let (to_index, to_is_new) = vertices.insert_full(to);
-
-                if from_is_new {
-                    obstacles.vertices.push(from.cast::<f64>() * 0.5);
-                }
-
-                if to_is_new {
-                    obstacles.vertices.push(to.cast::<f64>() * 0.5);
-                }
-
-                obstacles.edges.push((from_index, to_index));
-            };
-        }
-
-        let mut last_left_corner = Rotation2::new(direction + FRAC_PI_2 + FRAC_PI_4)
-            * Vector2::new(FRAC_1_SQRT_2, 0.0)
-            + origin;
-        let mut last_right_corner = Rotation2::new(direction - FRAC_PI_2 - FRAC_PI_4)
-            * Vector2::new(FRAC_1_SQRT_2, 0.0)
-            + origin;
-        add_wall!(last_left_corner, last_right_corner);
-        origin += Rotation2::new(direction) * Vector2::new(rng.gen_range(1..=5) as f64, 0.0);
-
-        'main: for _ in 0..rng.gen_range(7..=13) {
-            let mut turn_options =
-                heapless::Vec::<_, 2>::from_slice(&[TurnType::Left, TurnType::Right]).unwrap();
-
-            loop {
-                let rand_turn_index = rng.gen_range(0..turn_options.len());
-                let turn_type = turn_options[rand_turn_index];
-
-                match turn_type {
-                    TurnType::Left => direction += FRAC_PI_2,
-                    TurnType::Right => direction -= FRAC_PI_2,
-                }
-
-                let distance = rng.gen_range(1..=5);
-
-                if let Some(raycast_distance) = obstacles.raycast::<f64>(origin, direction) {
-                    if raycast_distance < 1.5 {
-                        match turn_type {
-                            TurnType::Left => direction -= FRAC_PI_2,
-                            TurnType::Right => direction += FRAC_PI_2,
-                        }
-                        turn_options.swap_remove(rand_turn_index);
-                        if turn_options.is_empty() {
-                            break 'main;
-                        }
-                        continue;
-                    } else if raycast_distance < distance as f64 + 0.5 {
-                        match turn_type {
-                            TurnType::Left => direction -= FRAC_PI_2,
-                            TurnType::Right => direction += FRAC_PI_2,
-                        }
-                        continue;
-                    }
-                }
-
-                match turn_type {
-                    TurnType::Left => {
-                        let new_left_corner = Rotation2::new(direction + FRAC_PI_4)
-                            * Vector2::new(FRAC_1_SQRT_2, 0.0)
-                            + origin;
-                        let new_right_corner = Rotation2::new(direction - FRAC_PI_2 - FRAC_PI_4)
-                            * Vector2::new(FRAC_1_SQRT_2, 0.0)
-                            + origin;
-                        add_wall!(last_left_corner, new_left_corner);
-                        add_wall!(last_right_corner, new_right_corner);
-                        last_left_corner = new_left_corner;
-                        last_right_corner = new_right_corner;
-                    }
-                    TurnType::Right => {
-                        let new_left_corner = Rotation2::new(direction + FRAC_PI_2 + FRAC_PI_4)
-                            * Vector2::new(FRAC_1_SQRT_2, 0.0)
-                            + origin;
-                        let new_right_corner = Rotation2::new(direction - FRAC_PI_4)
-                            * Vector2::new(FRAC_1_SQRT_2, 0.0)
-                            + origin;
-                        add_wall!(last_left_corner, new_left_corner);
-                        add_wall!(last_right_corner, new_right_corner);
-                        last_left_corner = new_left_corner;
-                        last_right_corner = new_right_corner;
-                    }
-                }
-                origin += Rotation2::new(direction) * Vector2::new(distance as f64, 0.0);
-
-                break;
-            }
-        }
-
-        let new_left_corner =
-            Rotation2::new(direction + FRAC_PI_4) * Vector2::new(FRAC_1_SQRT_2, 0.0) + origin;
-        let new_right_corner =
-            Rotation2::new(direction - FRAC_PI_4) * Vector2::new(FRAC_1_SQRT_2, 0.0) + origin;
-        add_wall!(last_left_corner, new_left_corner);
-        add_wall!(last_right_corner, new_right_corner);
-        add_wall!(new_left_corner, new_right_corner);
-        END_POINT.store(origin);
-        end_point = origin;
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        (*obstacles, end_point) = build_linear_maze(
+            &mut rng,
+            segment_range,
+            SIMBOT_ORIGIN.load(),
+            SIMBOT_DIRECTION.load(),
+        );
+        END_POINT.store(end_point);
 
         let mut maze = std::fs::File::create("maze.toml").expect("Failed to create maze.toml");
         writeln!(
@@ -180,11 +214,131 @@ pub(super) fn generate_linear_maze() -> (RwLockReadGuard<'static, Obstacles>, Ve
     (RwLockWriteGuard::downgrade(obstacles), end_point)
 }
 
+/// Builds the walls of a maze that starts at `origin` facing `direction`, returning them
+/// with the end point of the maze.
+fn build_linear_maze(
+    rng: &mut impl Rng,
+    segment_range: RangeInclusive<usize>,
+    mut origin: Vector2<f64>,
+    mut direction: f64,
+) -> (Obstacles, Vector2<f64>) {
+    let mut obstacles = Obstacles::default();
+    let mut vertices = IndexSet::<Vector2<isize>, FxBuildHasher>::default();
+
+    macro_rules! add_wall {
+        ($from:expr, $to:expr) => {
+            let from = $from / 0.5;
+            let to = $to / 0.5;
+            let from = Vector2::new(from.x.round() as isize, from.y.round() as isize);
+            let to = Vector2::new(to.x.round() as isize, to.y.round() as isize);
+
+            let (from_index, from_is_new) = vertices.insert_full(from);
+            let (to_index, to_is_new) = vertices.insert_full(to);
+
+            if from_is_new {
+                obstacles.vertices.push(from.cast::<f64>() * 0.5);
+            }
+
+            if to_is_new {
+                obstacles.vertices.push(to.cast::<f64>() * 0.5);
+            }
+
+            obstacles.edges.push((from_index, to_index));
+        };
+    }
+
+    let mut last_left_corner = Rotation2::new(direction + FRAC_PI_2 + FRAC_PI_4)
+        * Vector2::new(FRAC_1_SQRT_2, 0.0)
+        + origin;
+    let mut last_right_corner = Rotation2::new(direction - FRAC_PI_2 - FRAC_PI_4)
+        * Vector2::new(FRAC_1_SQRT_2, 0.0)
+        + origin;
+    add_wall!(last_left_corner, last_right_corner);
+    origin += Rotation2::new(direction) * Vector2::new(rng.gen_range(1..=5) as f64, 0.0);
+
+    'main: for _ in 0..rng.gen_range(segment_range) {
+        let mut turn_options =
+            heapless::Vec::<_, 2>::from_slice(&[TurnType::Left, TurnType::Right]).unwrap();
+
+        loop {
+            let rand_turn_index = rng.gen_range(0..turn_options.len());
+            let turn_type = turn_options[rand_turn_index];
+
+            match turn_type {
+                TurnType::Left => direction += FRAC_PI_2,
+                TurnType::Right => direction -= FRAC_PI_2,
+            }
+
+            let distance = rng.gen_range(1..=5);
+
+            if let Some(raycast_distance) = obstacles.raycast::<f64>(origin, direction) {
+                if raycast_distance < 1.5 {
+                    match turn_type {
+                        TurnType::Left => direction -= FRAC_PI_2,
+                        TurnType::Right => direction += FRAC_PI_2,
+                    }
+                    turn_options.swap_remove(rand_turn_index);
+                    if turn_options.is_empty() {
+                        break 'main;
+                    }
+                    continue;
+                } else if raycast_distance < distance as f64 + 0.5 {
+                    match turn_type {
+                        TurnType::Left => direction -= FRAC_PI_2,
+                        TurnType::Right => direction += FRAC_PI_2,
+                    }
+                    continue;
+                }
+            }
+
+            match turn_type {
+                TurnType::Left => {
+                    let new_left_corner = Rotation2::new(direction + FRAC_PI_4)
+                        * Vector2::new(FRAC_1_SQRT_2, 0.0)
+                        + origin;
+                    let new_right_corner = Rotation2::new(direction - FRAC_PI_2 - FRAC_PI_4)
+                        * Vector2::new(FRAC_1_SQRT_2, 0.0)
+                        + origin;
+                    add_wall!(last_left_corner, new_left_corner);
+                    add_wall!(last_right_corner, new_right_corner);
+                    last_left_corner = new_left_corner;
+                    last_right_corner = new_right_corner;
+                }
+                TurnType::Right => {
+                    let new_left_corner = Rotation2::new(direction + FRAC_PI_2 + FRAC_PI_4)
+                        * Vector2::new(FRAC_1_SQRT_2, 0.0)
+                        + origin;
+                    let new_right_corner = Rotation2::new(direction - FRAC_PI_4)
+                        * Vector2::new(FRAC_1_SQRT_2, 0.0)
+                        + origin;
+                    add_wall!(last_left_corner, new_left_corner);
+                    add_wall!(last_right_corner, new_right_corner);
+                    last_left_corner = new_left_corner;
+                    last_right_corner = new_right_corner;
+                }
+            }
+            origin += Rotation2::new(direction) * Vector2::new(distance as f64, 0.0);
+
+            break;
+        }
+    }
+
+    let new_left_corner =
+        Rotation2::new(direction + FRAC_PI_4) * Vector2::new(FRAC_1_SQRT_2, 0.0) + origin;
+    let new_right_corner =
+        Rotation2::new(direction - FRAC_PI_4) * Vector2::new(FRAC_1_SQRT_2, 0.0) + origin;
+    add_wall!(last_left_corner, new_left_corner);
+    add_wall!(last_right_corner, new_right_corner);
+    add_wall!(new_left_corner, new_right_corner);
+    (obstacles, origin)
+}
+
 impl SyncTask for LinearMazeSensor {
-    type Output = Result<String, String>;
+    type Output = MazeOutcome;
 
     fn run(mut self) -> Self::Output {
-        let (obstacles, end_point) = generate_linear_maze();
+        COLLISION_RADIUS.store(self.collision_radius);
+        let (obstacles, end_point) = generate_linear_maze(self.seed, self.segment_range.clone());
         let mut obstacles_obj = std::io::BufWriter::new(
             std::fs::File::create("maze.obj").expect("Failed to create maze.obj"),
         );
@@ -224,30 +378,49 @@ impl SyncTask for LinearMazeSensor {
         writeln!(obstacles_obj, "v {} {} 0.5", start_origin.x, start_origin.y)
             .expect("Failed to write to maze.obj");
 
-        let result = loop {
+        let mut outcome = MazeOutcome {
+            reached: false,
+            collided: false,
+            elapsed: None,
+            path_len: 0,
+            time_limit: self.time_limit,
+        };
+        loop {
             if COLLIDED.load(Ordering::Relaxed) {
-                break Err("Your program collided with an obstacle!".to_string());
+                outcome.collided = true;
+                break;
             }
             let origin = SIMBOT_ORIGIN.load();
-            if (origin - end_point).magnitude() <= 0.5 {
-                break Ok(format!(
-                    "Your program reached the end in {:.2} secs!",
-                    start_time.elapsed().as_secs_f32()
-                )
-                .green()
-                .to_string());
+            if (origin - end_point).magnitude() <= self.goal_tolerance {
+                outcome.reached = true;
+                outcome.elapsed = Some(start_time.elapsed());
+                break;
             }
+            let direction = SIMBOT_DIRECTION.load();
             self.raycast_callbacks
-                .call(obstacles.raycast(origin, SIMBOT_DIRECTION.load()).unwrap());
+                .call(obstacles.raycast(origin, direction).unwrap());
+            if !self.lidar_callbacks.is_empty_mut() {
+                let hits = lidar_scan(
+                    &obstacles,
+                    origin,
+                    direction,
+                    self.lidar_ray_count,
+                    self.lidar_fov,
+                );
+                self.lidar_callbacks.call(&hits);
+            }
             sleeper.sleep(REFRESH_RATE);
-            if start_time.elapsed().as_secs_f32() > 5.0 {
-                break Err("Your program took longer than 5 secs to reach the end".to_string());
+            if start_time.elapsed() > self.time_limit {
+                break;
             }
-        };
+        }
 
         let history_len = DRIVE_HISTORY.len();
-        for _ in 0..history_len {
-            let next = DRIVE_HISTORY.pop().unwrap();
+        outcome.path_len = history_len;
+        let history: Vec<_> = (0..history_len)
+            .map(|_| DRIVE_HISTORY.pop().unwrap())
+            .collect();
+        for next in &history {
             writeln!(obstacles_obj, "v {} {} -0.2", next.x, next.y)
                 .expect("Failed to write to maze.obj");
             writeln!(obstacles_obj, "v {} {} 0.5", next.x, next.y)
@@ -268,6 +441,94 @@ impl SyncTask for LinearMazeSensor {
         }
 
         obstacles_obj.flush().expect("Failed to write to maze.obj");
-        result
+
+        let mut maze_svg = std::io::BufWriter::new(
+            std::fs::File::create("maze.svg").expect("Failed to create maze.svg"),
+        );
+        write_svg(&mut maze_svg, &obstacles, start_origin, end_point, &history)
+            .and_then(|()| maze_svg.flush())
+            .expect("Failed to write to maze.svg");
+        outcome
+    }
+}
+
+/// Writes a top-down view of the maze and the path driven by the simbot as an SVG.
+///
+/// Walls are drawn in black, the path in red, the start point in blue, and the
+/// end point in green.
+fn write_svg(
+    mut writer: impl Write,
+    obstacles: &Obstacles,
+    start: Vector2<f64>,
+    end: Vector2<f64>,
+    history: &[Vector2<f64>],
+) -> std::io::Result<()> {
+    const MARGIN: f64 = 0.5;
+    let mut min = start.inf(&end);
+    let mut max = start.sup(&end);
+    for point in obstacles.vertices.iter().chain(history) {
+        min = min.inf(point);
+        max = max.sup(point);
+    }
+    min.add_scalar_mut(-MARGIN);
+    max.add_scalar_mut(MARGIN);
+    let size = max - min;
+
+    // SVG's y-axis points down, so all y coordinates are negated
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min.x, -max.y, size.x, size.y
+    )?;
+    for &(from, to) in &obstacles.edges {
+        let from = obstacles.vertices[from];
+        let to = obstacles.vertices[to];
+        writeln!(
+            writer,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="0.05"/>"#,
+            from.x, -from.y, to.x, -to.y
+        )?;
+    }
+    write!(writer, r#"<polyline points="{},{}"#, start.x, -start.y)?;
+    for point in history {
+        write!(writer, " {},{}", point.x, -point.y)?;
+    }
+    writeln!(
+        writer,
+        r#"" fill="none" stroke="red" stroke-width="0.05"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"<circle cx="{}" cy="{}" r="0.1" fill="blue"/>"#,
+        start.x, -start.y
+    )?;
+    writeln!(
+        writer,
+        r#"<circle cx="{}" cy="{}" r="0.1" fill="green"/>"#,
+        end.x, -end.y
+    )?;
+    writeln!(writer, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_maze_is_reproducible() {
+        let build = |seed| {
+            build_linear_maze(
+                &mut StdRng::seed_from_u64(seed),
+                7..=13,
+                Vector2::zeros(),
+                0.0,
+            )
+        };
+        let (first, first_end) = build(42);
+        let (second, second_end) = build(42);
+        assert!(!first.edges.is_empty());
+        assert_eq!(first.vertices, second.vertices);
+        assert_eq!(first.edges, second.edges);
+        assert_eq!(first_end, second_end);
     }
 }
@@ -19,6 +19,7 @@ static OBSTACLES: RwLock<Obstacles> = RwLock::new(Obstacles {
     edges: Vec::new(),
 });
 static COLLIDED: AtomicBool = AtomicBool::new(false);
+static COLLISION_RADIUS: AtomicCell<f64> = AtomicCell::new(0.0);
 static END_POINT: AtomicCell<Vector2<f64>> = AtomicCell::new(Vector2::new(0.0, 0.0));
 static DRIVE_HISTORY: SegQueue<Vector2<f64>> = SegQueue::new();
 
@@ -61,9 +62,10 @@ impl Drive {
         } else if let Some(raycast_distance) =
             OBSTACLES.read().raycast::<f64>(self.origin, self.direction)
         {
-            if raycast_distance <= distance {
+            let free_distance = raycast_distance - COLLISION_RADIUS.load();
+            if free_distance < distance {
                 COLLIDED.store(true, Ordering::Relaxed);
-                distance = raycast_distance;
+                distance = free_distance.max(0.0);
             }
         }
         let rot = Rotation2::new(self.direction);